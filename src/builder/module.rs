@@ -36,6 +36,7 @@ struct ModuleScaffold {
 	pub export: elements::ExportSection,
 	pub start: Option<u32>,
 	pub element: elements::ElementSection,
+	pub data_count: Option<u32>,
 	pub code: elements::CodeSection,
 	pub data: elements::DataSection,
 	pub other: Vec<elements::Section>,
@@ -52,6 +53,7 @@ impl From<elements::Module> for ModuleScaffold {
 		let mut export: Option<elements::ExportSection> = None;
 		let mut start: Option<u32> = None;
 		let mut element: Option<elements::ElementSection> = None;
+		let mut data_count: Option<u32> = None;
 		let mut code: Option<elements::CodeSection> = None;
 		let mut data: Option<elements::DataSection> = None;
 
@@ -68,6 +70,7 @@ impl From<elements::Module> for ModuleScaffold {
 				elements::Section::Export(sect) => { export = Some(sect); }
 				elements::Section::Start(index) => { start = Some(index); }
 				elements::Section::Element(sect) => { element = Some(sect); }
+				elements::Section::DataCount(count) => { data_count = Some(count); }
 				elements::Section::Code(sect) => { code = Some(sect); }
 				elements::Section::Data(sect) => { data = Some(sect); }
 				section => other.push(section)
@@ -84,6 +87,7 @@ impl From<elements::Module> for ModuleScaffold {
 			export: export.unwrap_or_default(),
 			start: start,
 			element: element.unwrap_or_default(),
+			data_count,
 			code: code.unwrap_or_default(),
 			data: data.unwrap_or_default(),
 			other,
@@ -130,6 +134,11 @@ impl From<ModuleScaffold> for elements::Module {
 		if element.entries().len() > 0 {
 			sections.push(elements::Section::Element(element));
 		}
+		// Data segments may have been added or removed, so the count is taken from
+		// the data section rather than the original declaration.
+		if module.data_count.is_some() {
+			sections.push(elements::Section::DataCount(module.data.entries().len() as u32));
+		}
 		let code = module.code;
 		if code.bodies().len() > 0 {
 			sections.push(elements::Section::Code(code));
@@ -603,4 +612,33 @@ mod tests {
 
 		assert_eq!(module.type_section().expect("type section failed").types().len(), 1);
 	}
- }
+
+	#[test]
+	fn data_count() {
+		let module = module()
+			.function()
+				.signature().build()
+				.body().build()
+				.build()
+			.data()
+				.offset(elements::Instruction::I32Const(0))
+				.value(vec![1u8])
+				.build()
+			.build();
+		let mut sections = module.into_sections();
+		sections.insert(sections.len() - 2, elements::Section::DataCount(1));
+
+		let module = super::from_module(elements::Module::new(sections))
+			.data()
+				.offset(elements::Instruction::I32Const(16))
+				.value(vec![2u8])
+				.build()
+			.build();
+
+		assert_eq!(module.data_count_section(), Some(2));
+		let orders: Vec<u8> = module.sections().iter().map(|s| s.order()).collect();
+		assert_eq!(orders, vec![0x01, 0x03, 0x0a, 0x0b, 0x0c]);
+		elements::Module::from_bytes(module.to_bytes().expect("serialization to succeed"))
+			.expect("deserialization to succeed");
+	}
+}
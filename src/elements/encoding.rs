@@ -0,0 +1,131 @@
+//! Hex and base64 text encodings of binary data.
+
+use alloc::{string::String, vec::Vec};
+use super::Error;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const BASE64_ALPHABET: &[u8; 64] =
+	b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as lowercase hex.
+pub fn encode_hex(data: &[u8]) -> String {
+	let mut result = String::with_capacity(data.len() * 2);
+	for &byte in data {
+		result.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+		result.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+	}
+	result
+}
+
+/// Decode hex (either case) into bytes.
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, Error> {
+	fn digit(c: u8) -> Result<u8, Error> {
+		match c {
+			b'0'..=b'9' => Ok(c - b'0'),
+			b'a'..=b'f' => Ok(c - b'a' + 10),
+			b'A'..=b'F' => Ok(c - b'A' + 10),
+			_ => Err(Error::InvalidHex),
+		}
+	}
+
+	let pairs = input.as_bytes().chunks_exact(2);
+	if !pairs.remainder().is_empty() {
+		return Err(Error::InvalidHex);
+	}
+
+	pairs
+		.map(|pair| Ok(digit(pair[0])? << 4 | digit(pair[1])?))
+		.collect()
+}
+
+/// Encode bytes as padded base64 using the standard alphabet.
+pub fn encode_base64(data: &[u8]) -> String {
+	let mut result = String::with_capacity(data.len() / 3 * 4 + 4);
+	for chunk in data.chunks(3) {
+		let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+		let group = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+		for i in 0..4 {
+			if i <= chunk.len() {
+				result.push(BASE64_ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+			} else {
+				result.push('=');
+			}
+		}
+	}
+	result
+}
+
+/// Decode base64 using the standard alphabet. Trailing padding is optional.
+pub fn decode_base64(input: &str) -> Result<Vec<u8>, Error> {
+	fn sextet(c: u8) -> Result<u32, Error> {
+		match c {
+			b'A'..=b'Z' => Ok((c - b'A') as u32),
+			b'a'..=b'z' => Ok((c - b'a' + 26) as u32),
+			b'0'..=b'9' => Ok((c - b'0' + 52) as u32),
+			b'+' => Ok(62),
+			b'/' => Ok(63),
+			_ => Err(Error::InvalidBase64),
+		}
+	}
+
+	let input = input.as_bytes();
+	let padding = input.iter().rev().take(2).take_while(|&&c| c == b'=').count();
+	let input = &input[..input.len() - padding];
+	match (input.len() % 4, padding) {
+		(1, _) => return Err(Error::InvalidBase64),
+		(_, 0) | (2, 2) | (3, 1) => {},
+		_ => return Err(Error::InvalidBase64),
+	}
+
+	let mut result = Vec::with_capacity(input.len() / 4 * 3 + 2);
+	for chunk in input.chunks(4) {
+		let mut group = 0u32;
+		for (i, &c) in chunk.iter().enumerate() {
+			group |= sextet(c)? << (18 - 6 * i);
+		}
+		let bytes = [(group >> 16) as u8, (group >> 8) as u8, group as u8];
+		result.extend_from_slice(&bytes[..chunk.len() - 1]);
+	}
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hex() {
+		assert_eq!(encode_hex(&[0x00, 0x61, 0x73, 0x6d, 0xff]), "0061736dff");
+		assert_eq!(decode_hex("0061736DfF").expect("valid hex"), vec![0x00, 0x61, 0x73, 0x6d, 0xff]);
+		assert_eq!(decode_hex("").expect("valid hex"), Vec::<u8>::new());
+		assert!(decode_hex("006").is_err());
+		assert!(decode_hex("0g").is_err());
+	}
+
+	#[test]
+	fn base64() {
+		let cases: &[(&[u8], &str)] = &[
+			(b"", ""),
+			(b"f", "Zg=="),
+			(b"fo", "Zm8="),
+			(b"foo", "Zm9v"),
+			(b"foob", "Zm9vYg=="),
+			(b"fooba", "Zm9vYmE="),
+			(b"foobar", "Zm9vYmFy"),
+		];
+		for &(raw, encoded) in cases {
+			assert_eq!(encode_base64(raw), encoded);
+			assert_eq!(decode_base64(encoded).expect("valid base64"), raw);
+			assert_eq!(decode_base64(encoded.trim_end_matches('=')).expect("valid unpadded base64"), raw);
+		}
+	}
+
+	#[test]
+	fn base64_invalid() {
+		assert!(decode_base64("Zm9v!").is_err());
+		assert!(decode_base64("Zm9vY").is_err());
+		assert!(decode_base64("Z===").is_err());
+		assert!(decode_base64("Zg=a").is_err());
+	}
+}
@@ -1,5 +1,6 @@
 use alloc::string::String;
-use super::{Deserialize, Serialize, Error, VarUint7, VarUint32};
+use super::{Deserialize, Serialize, SerializedSize, Error, VarUint7, VarUint32};
+use super::leb128::unsigned_size;
 use crate::io;
 
 /// Internal reference of the exported entry.
@@ -48,6 +49,15 @@ impl Serialize for Internal {
 	}
 }
 
+impl SerializedSize for Internal {
+	fn serialized_size(&self) -> usize {
+		let arg = match *self {
+			Internal::Function(arg) | Internal::Table(arg) | Internal::Memory(arg) | Internal::Global(arg) => arg,
+		};
+		1 + unsigned_size(arg.into())
+	}
+}
+
 /// Export entry.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExportEntry {
@@ -100,3 +110,9 @@ impl Serialize for ExportEntry {
 		Ok(())
 	}
 }
+
+impl SerializedSize for ExportEntry {
+	fn serialized_size(&self) -> usize {
+		self.field_str.serialized_size() + self.internal.serialized_size()
+	}
+}
@@ -1,8 +1,10 @@
 use alloc::vec::Vec;
 use super::{
 	Deserialize, Error, ValueType, VarUint32, CountedList, Instructions,
-	Serialize, CountedWriter, CountedListWriter,
+	Serialize, SerializedSize, CountedWriter, CountedListWriter,
 };
+use super::leb128::unsigned_size;
+use super::primitives::{counted_list_size, prefixed_size};
 use crate::{io, elements::section::SectionReader};
 
 /// Function signature (type reference)
@@ -32,6 +34,12 @@ impl Serialize for Func {
 	}
 }
 
+impl SerializedSize for Func {
+	fn serialized_size(&self) -> usize {
+		unsigned_size(self.0.into())
+	}
+}
+
 impl Deserialize for Func {
 	 type Error = Error;
 
@@ -80,6 +88,12 @@ impl Serialize for Local {
 	}
 }
 
+impl SerializedSize for Local {
+	fn serialized_size(&self) -> usize {
+		unsigned_size(self.count.into()) + self.value_type.serialized_size()
+	}
+}
+
 /// Function body definition.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FuncBody {
@@ -154,3 +168,9 @@ impl Serialize for FuncBody {
 		Ok(())
 	}
 }
+
+impl SerializedSize for FuncBody {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.locals) + self.instructions.serialized_size())
+	}
+}
@@ -1,5 +1,5 @@
 use crate::io;
-use super::{Deserialize, Serialize, Error, GlobalType, InitExpr};
+use super::{Deserialize, Serialize, SerializedSize, Error, GlobalType, InitExpr};
 
 /// Global entry in the module.
 #[derive(Clone, Debug, PartialEq)]
@@ -48,3 +48,9 @@ impl Serialize for GlobalEntry {
 		self.init_expr.serialize(writer)
 	}
 }
+
+impl SerializedSize for GlobalEntry {
+	fn serialized_size(&self) -> usize {
+		self.global_type.serialized_size() + self.init_expr.serialized_size()
+	}
+}
@@ -1,13 +1,20 @@
 use alloc::string::String;
 use crate::io;
 use super::{
-	Deserialize, Serialize, Error, VarUint7, VarInt7, VarUint32, VarUint1, Uint8,
-	ValueType, TableElementType
+	Deserialize, Serialize, SerializedSize, Error, VarUint7, VarInt7, VarUint32, VarUint1,
+	Uint8, ValueType, TableElementType
 };
+#[cfg(feature="memory64")]
+use super::VarUint64;
+use super::leb128::unsigned_size;
+use core::cmp;
+use core::convert::TryFrom;
 
 const FLAG_HAS_MAX: u8 = 0x01;
 #[cfg(feature="atomics")]
 const FLAG_SHARED: u8 = 0x02;
+#[cfg(feature="memory64")]
+const FLAG_MEMORY_64: u8 = 0x04;
 
 /// Global definition struct
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -55,6 +62,12 @@ impl Serialize for GlobalType {
 	}
 }
 
+impl SerializedSize for GlobalType {
+	fn serialized_size(&self) -> usize {
+		self.content_type.serialized_size() + 1
+	}
+}
+
 /// Table entry
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct TableType {
@@ -84,6 +97,15 @@ impl Deserialize for TableType {
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
 		let elem_type = TableElementType::deserialize(reader)?;
 		let limits = ResizableLimits::deserialize(reader)?;
+
+		// Only memories can be indexed by i64.
+		#[cfg(feature="memory64")]
+		{
+			if limits.memory64 {
+				return Err(Error::InvalidLimitsFlags(FLAG_MEMORY_64));
+			}
+		}
+
 		Ok(TableType {
 			elem_type: elem_type,
 			limits: limits,
@@ -100,33 +122,60 @@ impl Serialize for TableType {
 	}
 }
 
+impl SerializedSize for TableType {
+	fn serialized_size(&self) -> usize {
+		self.elem_type.serialized_size() + self.limits.serialized_size()
+	}
+}
+
 /// Memory and table limits.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct ResizableLimits {
-	initial: u32,
-	maximum: Option<u32>,
+	// 64-bit memories may declare limits that do not fit into u32.
+	initial: u64,
+	maximum: Option<u64>,
 	#[cfg(feature = "atomics")]
 	shared: bool,
+	#[cfg(feature = "memory64")]
+	memory64: bool,
 }
 
 impl ResizableLimits {
 	/// New memory limits definition.
 	pub fn new(min: u32, max: Option<u32>) -> Self {
 		ResizableLimits {
-			initial: min,
-			maximum: max,
+			initial: min.into(),
+			maximum: max.map(Into::into),
 			#[cfg(feature = "atomics")]
 			shared: false,
+			#[cfg(feature = "memory64")]
+			memory64: false,
 		}
 	}
 	/// Initial size.
-	pub fn initial(&self) -> u32 { self.initial }
+	///
+	/// Saturates at `u32::MAX` for 64-bit memories that declare more, see `initial64`.
+	pub fn initial(&self) -> u32 { cmp::min(self.initial, u32::MAX as u64) as u32 }
 	/// Maximum size.
-	pub fn maximum(&self) -> Option<u32> { self.maximum }
+	///
+	/// Saturates at `u32::MAX` for 64-bit memories that declare more, see `maximum64`.
+	pub fn maximum(&self) -> Option<u32> { self.maximum.map(|max| cmp::min(max, u32::MAX as u64) as u32) }
+
+	#[cfg(feature = "memory64")]
+	/// Initial size, as declared by a 64-bit memory.
+	pub fn initial64(&self) -> u64 { self.initial }
+
+	#[cfg(feature = "memory64")]
+	/// Maximum size, as declared by a 64-bit memory.
+	pub fn maximum64(&self) -> Option<u64> { self.maximum }
 
 	#[cfg(feature = "atomics")]
 	/// Whether or not this is a shared array buffer.
 	pub fn shared(&self) -> bool { self.shared }
+
+	#[cfg(feature = "memory64")]
+	/// Whether or not this is a memory indexed by i64.
+	pub fn memory64(&self) -> bool { self.memory64 }
 }
 
 impl Deserialize for ResizableLimits {
@@ -143,22 +192,43 @@ impl Deserialize for ResizableLimits {
 			#[cfg(feature="atomics")]
 			0x03 => {},
 
+			// Memory64 proposal introduces FLAG_MEMORY_64 (0x04), which can be combined with
+			// the other flags.
+			#[cfg(feature="memory64")]
+			0x04 | 0x05 => {},
+			#[cfg(all(feature="atomics", feature="memory64"))]
+			0x07 => {},
+
 			_ => return Err(Error::InvalidLimitsFlags(flags)),
 		}
 
-		let initial = VarUint32::deserialize(reader)?;
+		let read_limit = |reader: &mut R| -> Result<u64, Error> {
+			// 64-bit memories encode their limits as u64.
+			#[cfg(feature="memory64")]
+			{
+				if flags & FLAG_MEMORY_64 != 0 {
+					return Ok(VarUint64::deserialize(reader)?.into());
+				}
+			}
+			Ok(u32::from(VarUint32::deserialize(reader)?).into())
+		};
+
+		let initial = read_limit(reader)?;
 		let maximum = if flags & FLAG_HAS_MAX != 0 {
-			Some(VarUint32::deserialize(reader)?.into())
+			Some(read_limit(reader)?)
 		} else {
 			None
 		};
 
 		Ok(ResizableLimits {
-			initial: initial.into(),
+			initial,
 			maximum: maximum,
 
 			#[cfg(feature="atomics")]
 			shared: flags & FLAG_SHARED != 0,
+
+			#[cfg(feature="memory64")]
+			memory64: flags & FLAG_MEMORY_64 != 0,
 		})
 	}
 }
@@ -180,15 +250,41 @@ impl Serialize for ResizableLimits {
 				flags |= FLAG_SHARED;
 			}
 		}
+
+		#[cfg(feature="memory64")]
+		{
+			if self.memory64 {
+				flags |= FLAG_MEMORY_64;
+			}
+		}
 		Uint8::from(flags).serialize(writer)?;
-		VarUint32::from(self.initial).serialize(writer)?;
+
+		let write_limit = |limit: u64, writer: &mut W| -> Result<(), Error> {
+			// 64-bit memories encode their limits as u64.
+			#[cfg(feature="memory64")]
+			{
+				if flags & FLAG_MEMORY_64 != 0 {
+					return VarUint64::from(limit).serialize(writer);
+				}
+			}
+			let limit = u32::try_from(limit).map_err(|_| Error::InvalidVarUint32)?;
+			VarUint32::from(limit).serialize(writer)
+		};
+
+		write_limit(self.initial, writer)?;
 		if let Some(max) = self.maximum {
-			VarUint32::from(max).serialize(writer)?;
+			write_limit(max, writer)?;
 		}
 		Ok(())
 	}
 }
 
+impl SerializedSize for ResizableLimits {
+	fn serialized_size(&self) -> usize {
+		1 + unsigned_size(self.initial) + self.maximum.map_or(0, unsigned_size)
+	}
+}
+
 /// Memory entry.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct MemoryType(ResizableLimits);
@@ -208,6 +304,25 @@ impl MemoryType {
 		self.0.shared = shared;
 	}
 
+	/// New definition of a memory indexed by i64.
+	#[cfg(feature = "memory64")]
+	pub fn new64(min: u64, max: Option<u64>) -> Self {
+		let mut r = ResizableLimits::new(0, None);
+		r.initial = min;
+		r.maximum = max;
+		r.memory64 = true;
+		MemoryType(r)
+	}
+
+	/// Set the `memory64` flag that denotes a memory indexed by i64.
+	///
+	/// `false` by default. This is only available if the `memory64` feature is enabled.
+	/// Limits above u32 fail to serialize once the flag is cleared.
+	#[cfg(feature = "memory64")]
+	pub fn set_memory64(&mut self, memory64: bool) {
+		self.0.memory64 = memory64;
+	}
+
 	/// Limits of the memory entry.
 	pub fn limits(&self) -> &ResizableLimits {
 		&self.0
@@ -230,6 +345,12 @@ impl Serialize for MemoryType {
 	}
 }
 
+impl SerializedSize for MemoryType {
+	fn serialized_size(&self) -> usize {
+		self.0.serialized_size()
+	}
+}
+
 /// External to local binding.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum External {
@@ -288,6 +409,17 @@ impl Serialize for External {
 	}
 }
 
+impl SerializedSize for External {
+	fn serialized_size(&self) -> usize {
+		1 + match *self {
+			External::Function(index) => unsigned_size(index.into()),
+			External::Table(ref tt) => tt.serialized_size(),
+			External::Memory(ref mt) => mt.serialized_size(),
+			External::Global(ref gt) => gt.serialized_size(),
+		}
+	}
+}
+
 /// Import entry.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ImportEntry {
@@ -354,3 +486,63 @@ impl Serialize for ImportEntry {
 		self.external.serialize(writer)
 	}
 }
+
+impl SerializedSize for ImportEntry {
+	fn serialized_size(&self) -> usize {
+		self.module_str.serialized_size() + self.field_str.serialized_size() + self.external.serialized_size()
+	}
+}
+
+#[cfg(all(test, feature="memory64"))]
+mod tests {
+	use super::super::{deserialize_buffer, serialize};
+	use super::*;
+
+	#[test]
+	fn memory64_limits() {
+		let bytes = [0x05, 0x01, 0x80, 0x02];
+		let memory: MemoryType = deserialize_buffer(&bytes).expect("memory64 limits to decode");
+		assert!(memory.limits().memory64());
+		assert_eq!(memory.limits().initial(), 1);
+		assert_eq!(memory.limits().maximum(), Some(256));
+		assert_eq!(serialize(memory).expect("serialization to succeed"), bytes);
+
+		let mut memory = MemoryType::new(2, None);
+		memory.set_memory64(true);
+		assert_eq!(serialize(memory).expect("serialization to succeed"), vec![0x04, 0x02]);
+	}
+
+	#[test]
+	fn memory64_large_limits() {
+		// maximum = 2^32 pages
+		let bytes = [0x05, 0x01, 0x80, 0x80, 0x80, 0x80, 0x10];
+		let memory: MemoryType = deserialize_buffer(&bytes).expect("memory64 limits to decode");
+		assert_eq!(memory.limits().initial64(), 1);
+		assert_eq!(memory.limits().maximum64(), Some(1 << 32));
+		assert_eq!(memory.limits().maximum(), Some(u32::MAX));
+		assert_eq!(serialize(memory).expect("serialization to succeed"), bytes);
+
+		let memory = MemoryType::new64(1 << 48, None);
+		let parsed: MemoryType = deserialize_buffer(&serialize(memory).expect("serialization to succeed"))
+			.expect("memory64 limits to decode");
+		assert_eq!(parsed, memory);
+	}
+
+	#[test]
+	fn memory64_limits_cleared_flag() {
+		let mut memory = MemoryType::new64(1 << 40, None);
+		memory.set_memory64(false);
+		match serialize(memory) {
+			Err(Error::InvalidVarUint32) => {},
+			other => panic!("expected InvalidVarUint32, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn memory64_table_limits_rejected() {
+		match deserialize_buffer::<TableType>(&[0x70, 0x04, 0x01]) {
+			Err(Error::InvalidLimitsFlags(0x04)) => {},
+			other => panic!("expected invalid table limits, got {:?}", other),
+		}
+	}
+}
@@ -1,7 +1,9 @@
 use alloc::vec::Vec;
 use crate::io;
 
-use super::{Deserialize, Error, Serialize, VarUint32};
+use super::{Deserialize, Error, Serialize, SerializedSize, VarUint32};
+use super::leb128::unsigned_size;
+use super::primitives::check_vector_length;
 
 use alloc::vec;
 use core::{
@@ -157,7 +159,9 @@ impl<T> IndexMap<T> {
 		F: Fn(u32, &mut R) -> Result<T, Error>,
 	{
 		let len: u32 = VarUint32::deserialize(rdr)?.into();
-		let mut map = IndexMap::with_capacity(len as usize);
+		check_vector_length(rdr, len as usize)?;
+		// Only trust the declared length as far as the input can back it up.
+		let mut map = IndexMap::with_capacity(min(len as usize, rdr.remaining().unwrap_or(0)));
 		let mut prev_idx = None;
 		for _ in 0..len {
 			let idx: u32 = VarUint32::deserialize(rdr)?.into();
@@ -338,6 +342,14 @@ where
 	}
 }
 
+impl<T: SerializedSize + 'static> SerializedSize for IndexMap<T> {
+	fn serialized_size(&self) -> usize {
+		unsigned_size(self.len() as u64) + self.iter()
+			.map(|(idx, value)| unsigned_size(idx.into()) + value.serialized_size())
+			.sum::<usize>()
+	}
+}
+
 impl<T: Deserialize> IndexMap<T>
 where
 	T: Deserialize,
@@ -592,4 +604,16 @@ mod tests {
 		let res = IndexMap::<String>::deserialize(1, &mut io::Cursor::new(invalid));
 		assert!(res.is_err());
 	}
+
+	#[test]
+	fn deserialize_rejects_oversized_length() {
+		let mut invalid = vec![];
+		VarUint32::from(u32::MAX).serialize(&mut invalid).unwrap();
+		VarUint32::from(0u32).serialize(&mut invalid).unwrap();
+		"val 0".to_string().serialize(&mut invalid).unwrap();
+		match IndexMap::<String>::deserialize(usize::MAX, &mut io::Cursor::new(invalid)) {
+			Err(Error::InvalidVectorLength) => {},
+			other => panic!("expected InvalidVectorLength, got {:?}", other),
+		}
+	}
 }
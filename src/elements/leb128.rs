@@ -0,0 +1,257 @@
+//! LEB128 variable-length integer encoding.
+//!
+//! These routines back the `VarUint32`, `VarUint64`, `VarInt32` and `VarInt64`
+//! primitives, and can be used directly to read or patch single fields without
+//! decoding the whole module. Readers enforce the maximum encoded length for the
+//! given bit width (5 bytes for 32-bit values, 10 bytes for 64-bit values) and
+//! reject encodings whose unused bits in the last byte are not a proper extension.
+
+use crate::io;
+use super::Error;
+
+/// Read an unsigned LEB128-encoded 32-bit integer.
+pub fn read_u32<R: io::Read>(reader: &mut R) -> Result<u32, Error> {
+	let mut res = 0;
+	let mut shift = 0;
+	let mut u8buf = [0u8; 1];
+	loop {
+		if shift > 31 { return Err(Error::InvalidVarUint32); }
+
+		reader.read(&mut u8buf)?;
+		let b = u8buf[0] as u32;
+		res |= (b & 0x7f).checked_shl(shift).ok_or(Error::InvalidVarUint32)?;
+		shift += 7;
+		if (b >> 7) == 0 {
+			if shift >= 32 && (b as u8).leading_zeros() < 4 {
+				return Err(Error::InvalidVarUint32);
+			}
+			break;
+		}
+	}
+	Ok(res)
+}
+
+/// Read an unsigned LEB128-encoded 64-bit integer.
+pub fn read_u64<R: io::Read>(reader: &mut R) -> Result<u64, Error> {
+	let mut res = 0;
+	let mut shift = 0;
+	let mut u8buf = [0u8; 1];
+	loop {
+		if shift > 63 { return Err(Error::InvalidVarUint64); }
+
+		reader.read(&mut u8buf)?;
+		let b = u8buf[0] as u64;
+		res |= (b & 0x7f).checked_shl(shift).ok_or(Error::InvalidVarUint64)?;
+		shift += 7;
+		if (b >> 7) == 0 {
+			if shift >= 64 && (b as u8).leading_zeros() < 7 {
+				return Err(Error::InvalidVarUint64);
+			}
+			break;
+		}
+	}
+	Ok(res)
+}
+
+/// Read a signed LEB128-encoded 32-bit integer.
+pub fn read_i32<R: io::Read>(reader: &mut R) -> Result<i32, Error> {
+	let mut res = 0;
+	let mut shift = 0;
+	let mut u8buf = [0u8; 1];
+	loop {
+		if shift > 31 { return Err(Error::InvalidVarInt32); }
+		reader.read(&mut u8buf)?;
+		let b = u8buf[0];
+
+		res |= ((b & 0x7f) as i32).checked_shl(shift).ok_or(Error::InvalidVarInt32)?;
+
+		shift += 7;
+		if (b >> 7) == 0 {
+			if shift < 32 && b & 0b0100_0000 == 0b0100_0000 {
+				res |= (1i32 << shift).wrapping_neg();
+			} else if shift >= 32 && b & 0b0100_0000 == 0b0100_0000 {
+				if (!(b | 0b1000_0000)).leading_zeros() < 5 {
+					return Err(Error::InvalidVarInt32);
+				}
+			} else if shift >= 32 && b & 0b0100_0000 == 0 && b.leading_zeros() < 5 {
+				return Err(Error::InvalidVarInt32);
+			}
+			break;
+		}
+	}
+	Ok(res)
+}
+
+/// Read a signed LEB128-encoded 64-bit integer.
+pub fn read_i64<R: io::Read>(reader: &mut R) -> Result<i64, Error> {
+	let mut res = 0i64;
+	let mut shift = 0;
+	let mut u8buf = [0u8; 1];
+
+	loop {
+		if shift > 63 { return Err(Error::InvalidVarInt64); }
+		reader.read(&mut u8buf)?;
+		let b = u8buf[0];
+
+		res |= ((b & 0x7f) as i64).checked_shl(shift).ok_or(Error::InvalidVarInt64)?;
+
+		shift += 7;
+		if (b >> 7) == 0 {
+			if shift < 64 && b & 0b0100_0000 == 0b0100_0000 {
+				res |= (1i64 << shift).wrapping_neg();
+			} else if shift >= 64 && b & 0b0100_0000 == 0b0100_0000 {
+				if (b | 0b1000_0000) as i8 != -1 {
+					return Err(Error::InvalidVarInt64);
+				}
+			} else if shift >= 64 && b != 0 {
+				return Err(Error::InvalidVarInt64);
+			}
+			break;
+		}
+	}
+	Ok(res)
+}
+
+/// Write an unsigned 32-bit integer using the shortest LEB128 encoding.
+pub fn write_u32<W: io::Write>(writer: &mut W, value: u32) -> Result<(), Error> {
+	write_u64(writer, value as u64)
+}
+
+/// Write an unsigned 64-bit integer using the shortest LEB128 encoding.
+pub fn write_u64<W: io::Write>(writer: &mut W, value: u64) -> Result<(), Error> {
+	let mut buf = [0u8; 1];
+	let mut v = value;
+	loop {
+		buf[0] = (v & 0b0111_1111) as u8;
+		v >>= 7;
+		if v > 0 {
+			buf[0] |= 0b1000_0000;
+		}
+		writer.write(&buf[..])?;
+		if v == 0 { break; }
+	}
+
+	Ok(())
+}
+
+/// Write a signed 32-bit integer using the shortest LEB128 encoding.
+pub fn write_i32<W: io::Write>(writer: &mut W, value: i32) -> Result<(), Error> {
+	write_i64(writer, value as i64)
+}
+
+/// Write a signed 64-bit integer using the shortest LEB128 encoding.
+pub fn write_i64<W: io::Write>(writer: &mut W, value: i64) -> Result<(), Error> {
+	let mut buf = [0u8; 1];
+	let mut v = value;
+	let mut more = true;
+	while more {
+		buf[0] = (v & 0b0111_1111) as u8;
+		v >>= 7;
+		if (v == 0 && buf[0] & 0b0100_0000 == 0) || (v == -1 && buf[0] & 0b0100_0000 == 0b0100_0000)  {
+			more = false
+		} else {
+			buf[0] |= 0b1000_0000
+		}
+
+		writer.write(&buf[..])?;
+	}
+
+	Ok(())
+}
+
+/// Number of bytes `write_u64` (and `write_u32`) produces for `value`.
+pub(crate) fn unsigned_size(value: u64) -> usize {
+	let mut size = 1;
+	let mut v = value >> 7;
+	while v != 0 {
+		size += 1;
+		v >>= 7;
+	}
+	size
+}
+
+/// Number of bytes `write_i64` (and `write_i32`) produces for `value`.
+pub(crate) fn signed_size(value: i64) -> usize {
+	// Done once the remaining bits, including the sign bit of a byte, are sign extension.
+	let mut size = 1;
+	let mut v = value;
+	while v >> 6 != 0 && v >> 6 != -1 {
+		size += 1;
+		v >>= 7;
+	}
+	size
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::io;
+	use super::*;
+
+	#[test]
+	fn u32_roundtrip() {
+		for &val in &[0u32, 1, 127, 128, 624485, u32::MAX] {
+			let mut buf = Vec::new();
+			write_u32(&mut buf, val).expect("write to succeed");
+			let mut cursor = io::Cursor::new(&buf[..]);
+			assert_eq!(read_u32(&mut cursor).expect("read to succeed"), val);
+			assert_eq!(cursor.position(), buf.len());
+		}
+	}
+
+	#[test]
+	fn i64_roundtrip() {
+		for &val in &[0i64, -1, 63, -64, 64, -65, i64::MIN, i64::MAX] {
+			let mut buf = Vec::new();
+			write_i64(&mut buf, val).expect("write to succeed");
+			let mut cursor = io::Cursor::new(&buf[..]);
+			assert_eq!(read_i64(&mut cursor).expect("read to succeed"), val);
+			assert_eq!(cursor.position(), buf.len());
+		}
+	}
+
+	#[test]
+	fn shortest_encoding() {
+		let mut buf = Vec::new();
+		write_i32(&mut buf, -128).expect("write to succeed");
+		assert_eq!(buf, vec![0x80, 0x7f]);
+
+		let mut buf = Vec::new();
+		write_u64(&mut buf, 300).expect("write to succeed");
+		assert_eq!(buf, vec![0xac, 0x02]);
+	}
+
+	#[test]
+	fn sizes() {
+		for &val in &[0u64, 1, 127, 128, 16383, 16384, u32::MAX as u64, u64::MAX] {
+			let mut buf = Vec::new();
+			write_u64(&mut buf, val).expect("write to succeed");
+			assert_eq!(unsigned_size(val), buf.len(), "size of {}", val);
+		}
+		for &val in &[0i64, -1, 63, -64, 64, -65, 8191, -8192, 8192, i64::MIN, i64::MAX] {
+			let mut buf = Vec::new();
+			write_i64(&mut buf, val).expect("write to succeed");
+			assert_eq!(signed_size(val), buf.len(), "size of {}", val);
+		}
+	}
+
+	#[test]
+	fn overlong_rejected() {
+		let mut cursor = io::Cursor::new(&[0x80u8, 0x80, 0x80, 0x80, 0x80, 0x00][..]);
+		match read_u32(&mut cursor) {
+			Err(Error::InvalidVarUint32) => {},
+			_ => panic!("6-byte varuint32 should be rejected"),
+		}
+
+		let mut cursor = io::Cursor::new(&[0xffu8, 0xff, 0xff, 0xff, 0x1f][..]);
+		match read_u32(&mut cursor) {
+			Err(Error::InvalidVarUint32) => {},
+			_ => panic!("varuint32 with bits above 32 should be rejected"),
+		}
+
+		let mut cursor = io::Cursor::new(&[0x80u8, 0x80, 0x80, 0x80, 0x70][..]);
+		match read_i32(&mut cursor) {
+			Err(Error::InvalidVarInt32) => {},
+			_ => panic!("varint32 with a broken sign extension should be rejected"),
+		}
+	}
+}
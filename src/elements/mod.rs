@@ -35,6 +35,10 @@ mod segment;
 mod index_map;
 mod name_section;
 mod reloc_section;
+mod producers_section;
+mod encoding;
+
+pub mod leb128;
 
 pub use self::module::{Module, peek_size, ImportCountType};
 pub use self::section::{
@@ -79,6 +83,7 @@ pub use self::name_section::{
 pub use self::reloc_section::{
 	RelocSection, RelocationEntry,
 };
+pub use self::producers_section::{ProducersSection, ProducersField, ProducerValue};
 
 /// Deserialization from serial i/o.
 pub trait Deserialize : Sized {
@@ -97,6 +102,12 @@ pub trait Serialize {
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error>;
 }
 
+/// Number of bytes `Serialize` would write, computed from a reference.
+pub(crate) trait SerializedSize {
+	/// Encoded length in bytes.
+	fn serialized_size(&self) -> usize;
+}
+
 /// Deserialization/serialization error
 #[derive(Debug, Clone)]
 pub enum Error {
@@ -153,7 +164,7 @@ pub enum Error {
 	/// Invalid memory reference (should be 0).
 	InvalidMemoryReference(u8),
 	/// Invalid table reference (should be 0).
-	InvalidTableReference(u8),
+	InvalidTableReference(u32),
 	/// Invalid value used for flags in limits type.
 	InvalidLimitsFlags(u8),
 	/// Unknown function form (should be 0x60).
@@ -162,14 +173,26 @@ pub enum Error {
 	InvalidVarInt7(u8),
 	/// Number of function body entries and signatures does not match.
 	InconsistentCode,
+	/// Declared data count does not match the number of data segments.
+	InconsistentDataCount,
 	/// Only flags 0, 1, and 2 are accepted on segments.
 	InvalidSegmentFlags(u32),
+	/// Only flags 0 are accepted on `atomic.fence`.
+	InvalidFenceFlags(u8),
 	/// Sum of counts of locals is greater than 2^32.
 	TooManyLocals,
 	/// Duplicated name subsections.
 	DuplicatedNameSubsections(u8),
 	/// Unknown name subsection type.
 	UnknownNameSubsectionType(u8),
+	/// Input is not valid hex.
+	InvalidHex,
+	/// Input is not valid base64.
+	InvalidBase64,
+	/// Typed `select` must annotate exactly one result type.
+	InvalidSelectArity(u32),
+	/// Declared vector length is larger than the remaining input.
+	InvalidVectorLength,
 }
 
 impl fmt::Display for Error {
@@ -206,10 +229,16 @@ impl fmt::Display for Error {
 			Error::InvalidLimitsFlags(ref flags) =>  write!(f, "Invalid limits flags ({})", flags),
 			Error::UnknownFunctionForm(ref form) =>  write!(f, "Unknown function form ({})", form),
 			Error::InconsistentCode =>  write!(f, "Number of function body entries and signatures does not match"),
+			Error::InconsistentDataCount => write!(f, "Declared data count does not match the number of data segments"),
 			Error::InvalidSegmentFlags(n) =>  write!(f, "Invalid segment flags: {}", n),
+			Error::InvalidFenceFlags(n) => write!(f, "Invalid atomic.fence flags: {}", n),
 			Error::TooManyLocals => write!(f, "Too many locals"),
 			Error::DuplicatedNameSubsections(n) =>  write!(f, "Duplicated name subsections: {}", n),
 			Error::UnknownNameSubsectionType(n) => write!(f, "Unknown subsection type: {}", n),
+			Error::InvalidHex => write!(f, "Invalid hex encoding"),
+			Error::InvalidBase64 => write!(f, "Invalid base64 encoding"),
+			Error::InvalidSelectArity(n) => write!(f, "Invalid typed select arity: {}", n),
+			Error::InvalidVectorLength => write!(f, "Vector length exceeds remaining input"),
 		}
 	}
 }
@@ -247,10 +276,16 @@ impl ::std::error::Error for Error {
 			Error::InvalidLimitsFlags(_) => "Invalid limits flags",
 			Error::UnknownFunctionForm(_) =>  "Unknown function form",
 			Error::InconsistentCode =>  "Number of function body entries and signatures does not match",
+			Error::InconsistentDataCount => "Declared data count does not match the number of data segments",
 			Error::InvalidSegmentFlags(_) =>  "Invalid segment flags",
+			Error::InvalidFenceFlags(_) => "Invalid atomic.fence flags",
 			Error::TooManyLocals => "Too many locals",
 			Error::DuplicatedNameSubsections(_) =>  "Duplicated name subsections",
 			Error::UnknownNameSubsectionType(_) => "Unknown name subsections type",
+			Error::InvalidHex => "Invalid hex encoding",
+			Error::InvalidBase64 => "Invalid base64 encoding",
+			Error::InvalidSelectArity(_) => "Invalid typed select arity",
+			Error::InvalidVectorLength => "Vector length exceeds remaining input",
 		}
 	}
 }
@@ -281,9 +316,8 @@ impl Deserialize for Unparsed {
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
 		let len = VarUint32::deserialize(reader)?.into();
-		let mut vec = vec![0u8; len];
-		reader.read(&mut vec[..])?;
-		Ok(Unparsed(vec))
+		primitives::check_vector_length(reader, len)?;
+		Ok(Unparsed(buffered_read!(section::ENTRIES_BUFFER_LENGTH, len, reader)))
 	}
 }
 
@@ -305,6 +339,21 @@ pub fn deserialize_buffer<T: Deserialize>(contents: &[u8]) -> Result<T, T::Error
 	Ok(result)
 }
 
+/// Deserialize a module from a buffer, preserving sections with unknown ids.
+///
+/// Unlike `deserialize_buffer`, a non-custom section whose id is not known to this
+/// crate does not fail with `Error::InvalidSectionId`. It is kept as
+/// `Section::Unparsed` and serialized back verbatim. Known sections are decoded and
+/// checked as usual.
+pub fn deserialize_buffer_lenient(contents: &[u8]) -> Result<Module, Error> {
+	let mut reader = io::Cursor::new(contents);
+	let result = Module::deserialize_with(&mut reader, true)?;
+	if reader.position() != contents.len() {
+		return Err(io::Error::TrailingData.into())
+	}
+	Ok(result)
+}
+
 /// Create buffer with serialized value.
 pub fn serialize<T: Serialize>(val: T) -> Result<Vec<u8>, T::Error> {
 	let mut buf = Vec::new();
@@ -312,6 +361,30 @@ pub fn serialize<T: Serialize>(val: T) -> Result<Vec<u8>, T::Error> {
 	Ok(buf)
 }
 
+/// Deserialize deserializable type from hex-encoded buffer.
+///
+/// Malformed hex is reported as `Error::InvalidHex` before any decoding of `T` happens.
+pub fn deserialize_hex<T: Deserialize>(contents: &str) -> Result<T, T::Error> where T::Error: From<Error> {
+	deserialize_buffer(&encoding::decode_hex(contents)?)
+}
+
+/// Deserialize deserializable type from base64-encoded buffer (standard alphabet).
+///
+/// Malformed base64 is reported as `Error::InvalidBase64` before any decoding of `T` happens.
+pub fn deserialize_base64<T: Deserialize>(contents: &str) -> Result<T, T::Error> where T::Error: From<Error> {
+	deserialize_buffer(&encoding::decode_base64(contents)?)
+}
+
+/// Create lowercase hex string with serialized value.
+pub fn serialize_hex<T: Serialize>(val: T) -> Result<String, T::Error> {
+	Ok(encoding::encode_hex(&serialize(val)?))
+}
+
+/// Create padded base64 string (standard alphabet) with serialized value.
+pub fn serialize_base64<T: Serialize>(val: T) -> Result<String, T::Error> {
+	Ok(encoding::encode_base64(&serialize(val)?))
+}
+
 /// Deserialize module from the file.
 #[cfg(feature = "std")]
 pub fn deserialize_file<P: AsRef<::std::path::Path>>(p: P) -> Result<Module, Error> {
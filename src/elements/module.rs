@@ -1,7 +1,11 @@
 use alloc::{borrow::ToOwned, vec::Vec, string::String};
 use crate::io;
 
-use super::{deserialize_buffer, serialize, Deserialize, Serialize, Error, Uint32, External};
+use super::{
+	deserialize_buffer, serialize, Deserialize, Serialize, SerializedSize, Error, Uint32, External,
+	DataSegment, ElementSegment, ImportEntry, ExportEntry, Internal, Type, FunctionType, Func,
+	FuncBody, Local, Instructions,
+};
 use super::section::{
 	Section, CodeSection, TypeSection, ImportSection, ExportSection, FunctionSection,
 	GlobalSection, TableSection, ElementSection, DataSection, MemorySection,
@@ -9,6 +13,7 @@ use super::section::{
 };
 use super::name_section::NameSection;
 use super::reloc_section::RelocSection;
+use super::producers_section::ProducersSection;
 
 use core::cmp;
 
@@ -63,6 +68,15 @@ impl Module {
 		Ok(serialize::<Module>(self)?)
 	}
 
+	/// Number of bytes `serialize` would produce for this module.
+	///
+	/// The encoded lengths are summed from the sections in place, without cloning or
+	/// serializing them. The result is only meaningful if `serialize` succeeds.
+	pub fn serialized_size(&self) -> usize {
+		// Magic and version.
+		8 + self.sections.iter().map(SerializedSize::serialized_size).sum::<usize>()
+	}
+
 	/// Destructure the module, yielding sections
 	pub fn into_sections(self) -> Vec<Section> {
 		self.sections
@@ -224,6 +238,17 @@ impl Module {
 		None
 	}
 
+	/// Data segments of the module, in declaration order.
+	///
+	/// Returns an empty slice if there is no data section. Active segments carry the
+	/// index of the memory they initialize and an offset expression. With the `bulk`
+	/// feature, passive segments are also decoded: they have no offset (`offset()`
+	/// returns `None` and `passive()` returns `true`) and are only copied into memory
+	/// by an explicit `memory.init`.
+	pub fn data_segments(&self) -> &[DataSegment] {
+		self.data_section().map(|ds| ds.entries()).unwrap_or(&[])
+	}
+
 	/// Element section reference, if any.
 	pub fn elements_section(&self) -> Option<&ElementSection> {
 		for section in self.sections() {
@@ -240,6 +265,17 @@ impl Module {
 		None
 	}
 
+	/// Element segments of the module, in declaration order.
+	///
+	/// Returns an empty slice if there is no element section. Active segments carry the
+	/// index of the table they initialize and an offset expression. With the `bulk`
+	/// feature, passive segments are also decoded: they have no offset (`offset()`
+	/// returns `None` and `passive()` returns `true`). Declarative segments are not
+	/// supported by the decoder. Use `elements_section_mut` to rewrite the entries.
+	pub fn element_segments(&self) -> &[ElementSegment] {
+		self.elements_section().map(|es| es.entries()).unwrap_or(&[])
+	}
+
 	/// Memory section reference, if any.
 	pub fn memory_section(&self) -> Option<&MemorySection> {
 		for section in self.sections() {
@@ -280,6 +316,14 @@ impl Module {
 		None
 	}
 
+	/// Number of data segments declared by the data count section, if any.
+	pub fn data_count_section(&self) -> Option<u32> {
+		for section in self.sections() {
+			if let &Section::DataCount(count) = section { return Some(count); }
+		}
+		None
+	}
+
 	/// Changes the module's start section.
 	pub fn set_start_section(&mut self, new_start: u32) {
 		for section in self.sections_mut().iter_mut() {
@@ -475,6 +519,16 @@ impl Module {
 		}
 	}
 
+	/// Toolchain information from the "producers" custom section, if any.
+	///
+	/// The section is decoded on each call and left in place as a custom section.
+	/// Returns `None` if it is absent or malformed.
+	pub fn producers(&self) -> Option<ProducersSection> {
+		self.custom_sections()
+			.find(|custom| custom.name() == "producers")
+			.and_then(|custom| deserialize_buffer(custom.payload()).ok())
+	}
+
 	/// Count imports by provided type.
 	pub fn import_count(&self, count_type: ImportCountType) -> usize {
 		self.import_section()
@@ -489,6 +543,71 @@ impl Module {
 			.unwrap_or(0)
 	}
 
+	/// Module names this module imports from, in order of first appearance and without duplicates.
+	pub fn import_namespaces(&self) -> Vec<String> {
+		let mut namespaces: Vec<String> = Vec::new();
+		for entry in self.import_section().map(|is| is.entries()).unwrap_or(&[]) {
+			if !namespaces.iter().any(|ns| ns == entry.module()) {
+				namespaces.push(entry.module().to_owned());
+			}
+		}
+		namespaces
+	}
+
+	/// Import entries from the given module name, in declaration order.
+	pub fn imports_in(&self, namespace: &str) -> Vec<ImportEntry> {
+		self.import_section()
+			.map(|is| is.entries().iter().filter(|entry| entry.module() == namespace).cloned().collect())
+			.unwrap_or_default()
+	}
+
+	/// Append a function with the given signature, locals and body, returning its index in
+	/// the function index space.
+	///
+	/// An identical signature already in the type section is reused. Missing type, function
+	/// and code sections are created.
+	pub fn add_function(&mut self, signature: FunctionType, locals: Vec<Local>, code: Instructions) -> u32 {
+		if self.type_section().is_none() {
+			self.insert_section(Section::Type(Default::default())).expect("type section is absent; qed");
+		}
+		if self.function_section().is_none() {
+			self.insert_section(Section::Function(Default::default())).expect("function section is absent; qed");
+		}
+		if self.code_section().is_none() {
+			self.insert_section(Section::Code(Default::default())).expect("code section is absent; qed");
+		}
+
+		let index = self.functions_space() as u32;
+
+		let types = self.type_section_mut().expect("type section exists; qed").types_mut();
+		let signature = Type::Function(signature);
+		let type_ref = match types.iter().position(|t| *t == signature) {
+			Some(pos) => pos as u32,
+			None => {
+				types.push(signature);
+				types.len() as u32 - 1
+			}
+		};
+
+		self.function_section_mut().expect("function section exists; qed").entries_mut().push(Func::new(type_ref));
+		self.code_section_mut().expect("code section exists; qed").bodies_mut().push(FuncBody::new(locals, code));
+
+		index
+	}
+
+	/// Append an export entry, creating the export section if it is missing.
+	///
+	/// The index in `internal` is not checked against the module.
+	pub fn add_export(&mut self, name: impl Into<String>, internal: Internal) {
+		if self.export_section().is_none() {
+			self.insert_section(Section::Export(Default::default())).expect("export section is absent; qed");
+		}
+		self.export_section_mut()
+			.expect("export section exists; qed")
+			.entries_mut()
+			.push(ExportEntry::new(name.into(), internal));
+	}
+
 	/// Query functions space.
 	pub fn functions_space(&self) -> usize {
 		self.import_count(ImportCountType::Function) +
@@ -514,10 +633,10 @@ impl Module {
 	}
 }
 
-impl Deserialize for Module {
-	type Error = super::Error;
-
-	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+impl Module {
+	/// Deserialize a module. In lenient mode sections with an unknown id are kept as
+	/// `Section::Unparsed` instead of failing with `Error::InvalidSectionId`.
+	pub(crate) fn deserialize_with<R: io::Read>(reader: &mut R, lenient: bool) -> Result<Self, Error> {
 		let mut sections = Vec::new();
 
 		let mut magic = [0u8; 4];
@@ -535,7 +654,7 @@ impl Deserialize for Module {
 		let mut last_section_order = 0;
 
 		loop {
-			match Section::deserialize(reader) {
+			match Section::deserialize_with(reader, lenient) {
 				Err(Error::UnexpectedEof) => { break; },
 				Err(e) => { return Err(e) },
 				Ok(section) => {
@@ -564,10 +683,24 @@ impl Deserialize for Module {
 			return Err(Error::InconsistentCode);
 		}
 
+		if let Some(count) = module.data_count_section() {
+			if count as usize != module.data_section().map(|ds| ds.entries().len()).unwrap_or(0) {
+				return Err(Error::InconsistentDataCount);
+			}
+		}
+
 		Ok(module)
 	}
 }
 
+impl Deserialize for Module {
+	type Error = super::Error;
+
+	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+		Module::deserialize_with(reader, false)
+	}
+}
+
 impl Serialize for Module {
 	type Error = Error;
 
@@ -625,7 +758,7 @@ pub fn peek_size(source: &[u8]) -> usize {
 			(peek_section.cursor, section_id, section_len)
 		};
 
-		if section_id <= 11 && section_len > 0 {
+		if section_id <= 12 && section_len > 0 {
 			let next_cursor = cursor + new_cursor + section_len as usize;
 			if next_cursor > source.len() {
 				break;
@@ -966,6 +1099,352 @@ mod integration_tests {
         assert!(deserialize_buffer::<Module>(&serialized).is_ok());
     }
 
+    /// Name, bytes and parsed module of every test case in `res/cases/v1` that deserializes.
+    fn corpus() -> Vec<(String, Vec<u8>, Module)> {
+        let mut cases = Vec::new();
+        for entry in ::std::fs::read_dir("./res/cases/v1").expect("test cases directory to exist") {
+            let path = entry.expect("directory entry to be readable").path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("wasm") { continue; }
+            let name = path.file_stem().and_then(|s| s.to_str()).expect("test case name to be utf-8").to_owned();
+            // 30 MB, far too slow for a debug build.
+            if name == "clang" { continue; }
+
+            let bytes = ::std::fs::read(&path).expect("test case to be readable");
+            if let Ok(module) = deserialize_buffer(&bytes) {
+                cases.push((name, bytes, module));
+            }
+        }
+        cases
+    }
+
+    #[test]
+    fn serialized_size() {
+        for (name, _, module) in corpus() {
+            let size = module.serialized_size();
+            assert_eq!(size, serialize(module.clone()).expect("failed to serialize").len(), "size mismatch for {}", name);
+
+            let module = module.parse_names().unwrap_or_else(|(_, module)| module);
+            let module = module.parse_reloc().unwrap_or_else(|(_, module)| module);
+            let size = module.serialized_size();
+            assert_eq!(size, serialize(module).expect("failed to serialize").len(), "size mismatch for parsed {}", name);
+        }
+
+        assert_eq!(Module::default().serialized_size(), 8);
+    }
+
+    #[test]
+    fn text_encoding_roundtrip() {
+        use super::super::{serialize_hex, deserialize_hex, serialize_base64, deserialize_base64, Error};
+
+        let module = deserialize_file("./res/cases/v1/test5.wasm").expect("failed to deserialize");
+
+        let encoded = serialize_base64(module.clone()).expect("failed to serialize");
+        let decoded: Module = deserialize_base64(&encoded).expect("failed to deserialize");
+        assert_eq!(decoded, module);
+        assert_eq!(serialize_base64(decoded).expect("failed to serialize"), encoded);
+
+        let encoded = serialize_hex(module.clone()).expect("failed to serialize");
+        let decoded: Module = deserialize_hex(&encoded).expect("failed to deserialize");
+        assert_eq!(decoded, module);
+        assert_eq!(serialize_hex(decoded).expect("failed to serialize"), encoded);
+
+        match deserialize_hex::<Module>("0061736d0100000x") {
+            Err(Error::InvalidHex) => {},
+            other => panic!("expected invalid hex error, got {:?}", other),
+        }
+        match deserialize_base64::<Module>("AGFzbQE*AAA=") {
+            Err(Error::InvalidBase64) => {},
+            other => panic!("expected invalid base64 error, got {:?}", other),
+        }
+        match deserialize_hex::<Module>("0061736e01000000") {
+            Err(Error::InvalidMagic) => {},
+            other => panic!("expected invalid magic error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn data_segments() {
+        use super::super::{DataSection, DataSegment, InitExpr, Instruction};
+
+        assert!(Module::default().data_segments().is_empty());
+
+        let segments = vec![
+            DataSegment::new(0, Some(InitExpr::new(vec![Instruction::I32Const(16), Instruction::End])), vec![1, 2, 3]),
+            DataSegment::new(0, Some(InitExpr::new(vec![Instruction::I32Const(64), Instruction::End])), b"hello".to_vec()),
+        ];
+        let module = Module::new(vec![Section::Data(DataSection::with_entries(segments.clone()))]);
+        let module = Module::from_bytes(module.to_bytes().expect("failed to serialize")).expect("failed to deserialize");
+
+        assert_eq!(module.data_segments(), &segments[..]);
+        assert_eq!(module.data_segments()[1].value(), b"hello");
+    }
+
+    #[test]
+    fn element_segments() {
+        use super::super::{ElementSection, ElementSegment, InitExpr, Instruction};
+
+        assert!(Module::default().element_segments().is_empty());
+
+        let segments = vec![
+            ElementSegment::new(0, Some(InitExpr::new(vec![Instruction::I32Const(0), Instruction::End])), vec![2, 0, 1]),
+            ElementSegment::new(0, Some(InitExpr::new(vec![Instruction::I32Const(8), Instruction::End])), vec![3]),
+        ];
+        let module = Module::new(vec![Section::Element(ElementSection::with_entries(segments.clone()))]);
+        let module = Module::from_bytes(module.to_bytes().expect("failed to serialize")).expect("failed to deserialize");
+
+        assert_eq!(module.element_segments(), &segments[..]);
+        assert_eq!(module.element_segments()[0].members(), &[2, 0, 1]);
+    }
+
+    #[test]
+    fn producers() {
+        use super::super::{ProducersSection, ProducersField, ProducerValue};
+
+        let mut module = Module::default();
+        assert_eq!(module.producers(), None);
+
+        let section = ProducersSection::new(vec![
+            ProducersField::new("processed-by".into(), vec![ProducerValue::new("rustc".into(), "1.50.0".into())]),
+        ]);
+        module.set_custom_section("producers", serialize(section.clone()).expect("serialization to succeed"));
+        assert_eq!(module.producers(), Some(section));
+
+        module.set_custom_section("producers", vec![0x01, 0x05, b'x']);
+        assert_eq!(module.producers(), None);
+    }
+
+    #[test]
+    fn malformed_input_does_not_panic() {
+        // Deterministic xorshift, so a failure can be reproduced.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || { state ^= state << 13; state ^= state >> 7; state ^= state << 17; state };
+
+        let corpus: Vec<Vec<u8>> = ["test", "test2", "names", "offset", "two-mems", "with_names", "relocatable"]
+            .iter()
+            .map(|name| ::std::fs::read(format!("./res/cases/v1/{}.wasm", name)).expect("test case to be readable"))
+            .collect();
+
+        for i in 0..2000 {
+            let data = if i % 2 == 0 {
+                let mut data = corpus[next() as usize % corpus.len()].clone();
+                for _ in 0..1 + next() % 4 {
+                    if data.is_empty() { break; }
+                    let pos = next() as usize % data.len();
+                    match next() % 3 {
+                        0 => data[pos] = next() as u8,
+                        1 => data[pos] ^= 1 << (next() % 8),
+                        _ => data.truncate(pos),
+                    }
+                }
+                data
+            } else {
+                let mut data = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+                data.extend((0..next() % 64).map(|_| next() as u8));
+                data
+            };
+
+            // Errors are fine, panics are not.
+            if let Ok(module) = Module::from_bytes(&data) {
+                let _ = module.clone().parse_names();
+                let _ = module.clone().parse_reloc();
+                let _ = module.to_bytes();
+            }
+        }
+    }
+
+    #[test]
+    fn import_namespaces() {
+        use super::super::{ImportEntry, ImportSection, External, GlobalType, ValueType};
+
+        let import = |module: &str, field: &str| ImportEntry::new(
+            module.into(), field.into(), External::Global(GlobalType::new(ValueType::I32, false)),
+        );
+        let module = Module::new(vec![Section::Import(ImportSection::with_entries(vec![
+            import("env", "a"),
+            import("wasi_snapshot_preview1", "fd_write"),
+            import("env", "b"),
+        ]))]);
+
+        assert_eq!(module.import_namespaces(), vec!["env".to_string(), "wasi_snapshot_preview1".to_string()]);
+        let env: Vec<_> = module.imports_in("env").into_iter().map(|e| e.field().to_string()).collect();
+        assert_eq!(env, vec!["a", "b"]);
+        assert!(module.imports_in("other").is_empty());
+        assert!(Module::default().import_namespaces().is_empty());
+    }
+
+    #[test]
+    fn add_function() {
+        use super::super::{FunctionType, Instruction, Instructions, Internal, Local, ValueType};
+
+        let mut module = deserialize_file("./res/cases/v1/start_add.wasm").expect("failed to deserialize");
+        let functions = module.functions_space() as u32;
+        let types = module.type_section().expect("type section to exist").types().len();
+        let add = module.type_section().expect("type section to exist").types()[0].clone();
+        let super::super::Type::Function(add) = add;
+
+        let first = module.add_function(
+            add.clone(),
+            vec![],
+            Instructions::new(vec![Instruction::GetLocal(0), Instruction::GetLocal(1), Instruction::I32Add, Instruction::End]),
+        );
+        let second = module.add_function(
+            FunctionType::new(vec![], vec![ValueType::I64]),
+            vec![Local::new(1, ValueType::I64)],
+            Instructions::new(vec![Instruction::GetLocal(0), Instruction::End]),
+        );
+        module.add_export("trampoline", Internal::Function(second));
+
+        assert_eq!(first, functions);
+        assert_eq!(second, functions + 1);
+        // The first signature is shared with an existing function.
+        assert_eq!(module.type_section().expect("type section to exist").types().len(), types + 1);
+        assert_eq!(module.function_section().expect("function section to exist").entries()[first as usize - module.import_count(super::ImportCountType::Function)].type_ref(), 0);
+
+        let module = Module::from_bytes(module.to_bytes().expect("serialization to succeed")).expect("deserialization to succeed");
+        let export = module.export_section().expect("export section to exist").entries().last().expect("export to exist").clone();
+        assert_eq!(export.field(), "trampoline");
+        assert_eq!(*export.internal(), Internal::Function(second));
+        assert_eq!(module.code_section().expect("code section to exist").bodies().len(), module.function_section().expect("function section to exist").entries().len());
+    }
+
+    #[test]
+    fn add_function_to_empty_module() {
+        use super::super::{FunctionType, Instruction, Instructions, Internal};
+
+        let mut module = Module::default();
+        let index = module.add_function(FunctionType::default(), vec![], Instructions::new(vec![Instruction::End]));
+        module.add_export("main", Internal::Function(index));
+        assert_eq!(index, 0);
+
+        let module = Module::from_bytes(module.to_bytes().expect("serialization to succeed")).expect("deserialization to succeed");
+        assert_eq!(module.functions_space(), 1);
+        assert_eq!(module.export_section().expect("export section to exist").entries().len(), 1);
+    }
+
+    fn data_count_module(count: u32) -> Module {
+        use super::super::{
+            CodeSection, DataSection, DataSegment, Func, FuncBody, FunctionSection, FunctionType,
+            InitExpr, Instruction, Instructions, Type, TypeSection,
+        };
+
+        Module::new(vec![
+            Section::Type(TypeSection::with_types(vec![Type::Function(FunctionType::default())])),
+            Section::Function(FunctionSection::with_entries(vec![Func::new(0)])),
+            Section::DataCount(count),
+            Section::Code(CodeSection::with_bodies(vec![FuncBody::new(vec![], Instructions::new(vec![Instruction::End]))])),
+            Section::Data(DataSection::with_entries(vec![DataSegment::new(
+                0,
+                Some(InitExpr::new(vec![Instruction::I32Const(0), Instruction::End])),
+                vec![1, 2, 3],
+            )])),
+        ])
+    }
+
+    #[test]
+    fn data_count_roundtrip() {
+        let bytes = data_count_module(1).to_bytes().expect("serialization to succeed");
+        assert_eq!(super::peek_size(&bytes), bytes.len());
+
+        let module = Module::from_bytes(&bytes).expect("deserialization to succeed");
+        assert_eq!(module.data_count_section(), Some(1));
+        assert_eq!(module.serialized_size(), bytes.len());
+        assert_eq!(module.to_bytes().expect("serialization to succeed"), bytes);
+    }
+
+    #[test]
+    fn data_count_mismatch() {
+        let bytes = data_count_module(2).to_bytes().expect("serialization to succeed");
+        match Module::from_bytes(&bytes) {
+            Err(super::super::Error::InconsistentDataCount) => {},
+            other => panic!("expected InconsistentDataCount, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_section_lenient() {
+        use super::super::{deserialize_buffer_lenient, Error};
+
+        let bytes = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+            // Type section with a single `() -> ()` signature.
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+            // Section 0x7f with a padded length prefix.
+            0x7f, 0x83, 0x00, 0xde, 0xad, 0x00,
+            // Start section, ordered after the type section.
+            0x08, 0x01, 0x00,
+        ];
+
+        match Module::from_bytes(&bytes[..]) {
+            Err(Error::InvalidSectionId(0x7f)) => {},
+            other => panic!("expected InvalidSectionId, got {:?}", other),
+        }
+
+        let module = deserialize_buffer_lenient(&bytes).expect("lenient deserialization to succeed");
+        assert_eq!(module.sections()[1], Section::Unparsed { id: 0x7f, payload: vec![0x83, 0x00, 0xde, 0xad, 0x00] });
+        assert_eq!(module.type_section().expect("type section to exist").types().len(), 1);
+        assert_eq!(module.serialized_size(), bytes.len());
+        assert_eq!(module.to_bytes().expect("serialization to succeed"), &bytes[..]);
+    }
+
+    #[cfg(feature = "reference_types")]
+    #[test]
+    fn reference_value_types() {
+        use super::super::{
+            FunctionType, GlobalEntry, GlobalSection, GlobalType, InitExpr, Instruction, Instructions, Local,
+            TableElementType, ValueType,
+        };
+
+        let local: Local = deserialize_buffer(&[0x01, 0x6f]).expect("externref local to decode");
+        assert_eq!(local.value_type(), ValueType::ExternRef);
+
+        let mut module = Module::default();
+        module.add_function(
+            FunctionType::new(vec![ValueType::ExternRef], vec![ValueType::FuncRef]),
+            vec![Local::new(1, ValueType::ExternRef)],
+            Instructions::new(vec![Instruction::RefNull(TableElementType::AnyFunc), Instruction::End]),
+        );
+        module.insert_section(Section::Global(GlobalSection::with_entries(vec![GlobalEntry::new(
+            GlobalType::new(ValueType::FuncRef, false),
+            InitExpr::new(vec![Instruction::RefNull(TableElementType::AnyFunc), Instruction::End]),
+        )]))).expect("global section is absent");
+
+        let bytes = module.clone().to_bytes().expect("serialization to succeed");
+        assert_eq!(Module::from_bytes(&bytes).expect("deserialization to succeed"), module);
+    }
+
+    #[test]
+    fn oversized_vector_length() {
+        // Type section of 5 bytes declaring u32::MAX types.
+        let bytes = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0xff, 0xff, 0xff, 0xff, 0x0f];
+        match Module::from_bytes(&bytes[..]) {
+            Err(super::super::Error::InvalidVectorLength) => {},
+            other => panic!("expected InvalidVectorLength, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stable_serialization() {
+        // These are encoded with minimal LEB128 throughout, so re-serialization must reproduce them exactly.
+        // The other cases use padded section lengths (as emitted by LLVM) and only have to reach a fixed point.
+        let canonical = [
+            "const", "ifelse", "names", "names_with_imports", "offset", "peek_sample",
+            "start_add", "start_add_custom", "start_mut", "test", "test2", "test3", "test6",
+            "two-mems", "with_names",
+        ];
+
+        let cases = corpus();
+        for (name, original, module) in &cases {
+            let serialized = serialize(module.clone()).expect("serialization to succeed");
+            if canonical.contains(&name.as_str()) {
+                assert_eq!(original, &serialized, "{} did not round-trip byte for byte", name);
+            }
+
+            let reparsed: Module = deserialize_buffer(&serialized).expect("serialized module to deserialize");
+            assert_eq!(serialized, serialize(reparsed).expect("serialization to succeed"), "{} serialization is not stable", name);
+        }
+        assert!(cases.len() >= canonical.len());
+    }
+
     #[test]
     fn serialization_roundtrip() {
         let module = deserialize_file("./res/cases/v1/test.wasm").expect("failed to deserialize");
@@ -1,7 +1,8 @@
 use alloc::{string::String, vec::Vec};
 use crate::io;
 
-use super::{Deserialize, Error, Module, Serialize, VarUint32, VarUint7, Type};
+use super::{Deserialize, Error, Module, Serialize, SerializedSize, VarUint32, VarUint7, Type};
+use super::primitives::prefixed_size;
 use super::index_map::IndexMap;
 
 const NAME_TYPE_MODULE: u8 = 0;
@@ -150,6 +151,17 @@ impl Serialize for NameSection {
 	}
 }
 
+impl SerializedSize for NameSection {
+	fn serialized_size(&self) -> usize {
+		// Each subsection is preceded by its type and length.
+		fn subsection_size<T: SerializedSize>(subsection: &Option<T>) -> usize {
+			subsection.as_ref().map_or(0, |s| 1 + prefixed_size(s.serialized_size()))
+		}
+
+		subsection_size(&self.module) + subsection_size(&self.functions) + subsection_size(&self.locals)
+	}
+}
+
 /// The name of this module.
 #[derive(Clone, Debug, PartialEq)]
 pub struct ModuleNameSubsection {
@@ -181,6 +193,12 @@ impl Serialize for ModuleNameSubsection {
 	}
 }
 
+impl SerializedSize for ModuleNameSubsection {
+	fn serialized_size(&self) -> usize {
+		self.name.serialized_size()
+	}
+}
+
 impl Deserialize for ModuleNameSubsection {
 	type Error = Error;
 
@@ -225,6 +243,12 @@ impl Serialize for FunctionNameSubsection {
 	}
 }
 
+impl SerializedSize for FunctionNameSubsection {
+	fn serialized_size(&self) -> usize {
+		self.names.serialized_size()
+	}
+}
+
 /// The names of the local variables in this module's functions.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct LocalNameSubsection {
@@ -295,6 +319,12 @@ impl Serialize for LocalNameSubsection {
 	}
 }
 
+impl SerializedSize for LocalNameSubsection {
+	fn serialized_size(&self) -> usize {
+		self.local_names.serialized_size()
+	}
+}
+
 /// A map from indices to names.
 pub type NameMap = IndexMap<String>;
 
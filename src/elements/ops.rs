@@ -1,11 +1,19 @@
 use alloc::{boxed::Box, vec::Vec};
 use crate::io;
 use super::{
-	Serialize, Deserialize, Error,
+	Serialize, SerializedSize, Deserialize, Error,
 	Uint8, VarUint32, CountedList, BlockType,
 	Uint32, Uint64, CountedListWriter,
 	VarInt32, VarInt64,
 };
+#[cfg(feature="reference_types")]
+use super::{ValueType, TableElementType};
+#[cfg(feature="memory64")]
+use super::VarUint64;
+#[cfg(not(feature="memory64"))]
+use core::convert::TryFrom;
+use super::leb128::unsigned_size;
+use super::primitives::ByteCounter;
 use core::fmt;
 
 /// List of instructions (usually inside a block section).
@@ -119,10 +127,15 @@ pub enum Instruction {
 	Return,
 
 	Call(u32),
-	CallIndirect(u32, u8),
+	/// Type index and table index.
+	///
+	/// Only table 0 is encodable without the `reference_types` feature.
+	CallIndirect(u32, u32),
 
 	Drop,
 	Select,
+	#[cfg(feature="reference_types")]
+	TypedSelect(ValueType),
 
 	GetLocal(u32),
 	SetLocal(u32),
@@ -130,6 +143,18 @@ pub enum Instruction {
 	GetGlobal(u32),
 	SetGlobal(u32),
 
+	#[cfg(feature="reference_types")]
+	TableGet(u32),
+	#[cfg(feature="reference_types")]
+	TableSet(u32),
+
+	#[cfg(feature="reference_types")]
+	RefNull(TableElementType),
+	#[cfg(feature="reference_types")]
+	RefIsNull,
+	#[cfg(feature="reference_types")]
+	RefFunc(u32),
+
 	// All store/load instructions operate with 'memory immediates'
 	// which represented here as (flag, offset) tuple
 	I32Load(MultiMemArg),
@@ -315,6 +340,7 @@ pub enum AtomicsInstruction {
 	AtomicWake(MemArg),
 	I32AtomicWait(MemArg),
 	I64AtomicWait(MemArg),
+	AtomicFence,
 
 	I32AtomicLoad(MemArg),
 	I64AtomicLoad(MemArg),
@@ -564,16 +590,30 @@ pub enum BulkInstruction {
 	MemoryDrop(u32),
 	MemoryCopy,
 	MemoryFill,
-	TableInit(u32),
+	/// Element segment index and table index.
+	///
+	/// Only table 0 is encodable without the `reference_types` feature.
+	TableInit(u32, u32),
 	TableDrop(u32),
-	TableCopy,
+	/// Destination and source table indices.
+	///
+	/// Only table 0 is encodable without the `reference_types` feature.
+	TableCopy(u32, u32),
+	#[cfg(feature="reference_types")]
+	TableGrow(u32),
+	#[cfg(feature="reference_types")]
+	TableSize(u32),
+	#[cfg(feature="reference_types")]
+	TableFill(u32),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[allow(missing_docs)]
 pub struct MultiMemArg {
 	/// A static offset to add to the instruction's dynamic address operand.
-	pub offset: u32,
+	///
+	/// Only 64-bit memories allow offsets beyond u32.
+	pub offset: u64,
 	/// The expected alignment of the instruction's dynamic address operand
 	/// (expressed the exponent of a power of two).
 	pub align: u32,
@@ -586,7 +626,7 @@ pub struct MultiMemArg {
 #[allow(missing_docs)]
 pub struct MemArg {
 	pub align: u8,
-	pub offset: u32,
+	pub offset: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -633,11 +673,17 @@ pub mod opcodes {
 	pub const CALLINDIRECT: u8 = 0x11;
 	pub const DROP: u8 = 0x1a;
 	pub const SELECT: u8 = 0x1b;
+	#[cfg(feature="reference_types")]
+	pub const TYPED_SELECT: u8 = 0x1c;
 	pub const GETLOCAL: u8 = 0x20;
 	pub const SETLOCAL: u8 = 0x21;
 	pub const TEELOCAL: u8 = 0x22;
 	pub const GETGLOBAL: u8 = 0x23;
 	pub const SETGLOBAL: u8 = 0x24;
+	#[cfg(feature="reference_types")]
+	pub const TABLE_GET: u8 = 0x25;
+	#[cfg(feature="reference_types")]
+	pub const TABLE_SET: u8 = 0x26;
 	pub const I32LOAD: u8 = 0x28;
 	pub const I64LOAD: u8 = 0x29;
 	pub const F32LOAD: u8 = 0x2a;
@@ -797,6 +843,13 @@ pub mod opcodes {
 	pub const F32REINTERPRETI32: u8 = 0xbe;
 	pub const F64REINTERPRETI64: u8 = 0xbf;
 
+	#[cfg(feature="reference_types")]
+	pub const REF_NULL: u8 = 0xd0;
+	#[cfg(feature="reference_types")]
+	pub const REF_IS_NULL: u8 = 0xd1;
+	#[cfg(feature="reference_types")]
+	pub const REF_FUNC: u8 = 0xd2;
+
 	#[cfg(feature="sign_ext")]
 	pub mod sign_ext {
 		pub const I32_EXTEND8_S: u8 = 0xc0;
@@ -812,6 +865,7 @@ pub mod opcodes {
 		pub const ATOMIC_WAKE: u8 = 0x00;
 		pub const I32_ATOMIC_WAIT: u8 = 0x01;
 		pub const I64_ATOMIC_WAIT: u8 = 0x02;
+		pub const ATOMIC_FENCE: u8 = 0x03;
 
 		pub const I32_ATOMIC_LOAD: u8 = 0x10;
 		pub const I64_ATOMIC_LOAD: u8 = 0x11;
@@ -1061,6 +1115,12 @@ pub mod opcodes {
 		pub const TABLE_INIT: u8 = 0x0c;
 		pub const TABLE_DROP: u8 = 0x0d;
 		pub const TABLE_COPY: u8 = 0x0e;
+		#[cfg(feature="reference_types")]
+		pub const TABLE_GROW: u8 = 0x0f;
+		#[cfg(feature="reference_types")]
+		pub const TABLE_SIZE: u8 = 0x10;
+		#[cfg(feature="reference_types")]
+		pub const TABLE_FILL: u8 = 0x11;
 	}
 }
 
@@ -1102,24 +1162,40 @@ impl Deserialize for Instruction {
 				},
 				RETURN => Return,
 				CALL => Call(VarUint32::deserialize(reader)?.into()),
+				#[cfg(not(feature="reference_types"))]
 				CALLINDIRECT => {
 					let signature: u32 = VarUint32::deserialize(reader)?.into();
 					let table_ref: u8 = Uint8::deserialize(reader)?.into();
-					if table_ref != 0 { return Err(Error::InvalidTableReference(table_ref)); }
+					if table_ref != 0 { return Err(Error::InvalidTableReference(table_ref.into())); }
 
 					CallIndirect(
 						signature,
-						table_ref,
+						0,
 					)
 				},
+				#[cfg(feature="reference_types")]
+				CALLINDIRECT => CallIndirect(
+					VarUint32::deserialize(reader)?.into(),
+					VarUint32::deserialize(reader)?.into(),
+				),
 				DROP => Drop,
 				SELECT => Select,
+				#[cfg(feature="reference_types")]
+				TYPED_SELECT => {
+					let types: Vec<ValueType> = CountedList::deserialize(reader)?.into_inner();
+					if types.len() != 1 { return Err(Error::InvalidSelectArity(types.len() as u32)); }
+					TypedSelect(types[0])
+				},
 
 				GETLOCAL => GetLocal(VarUint32::deserialize(reader)?.into()),
 				SETLOCAL => SetLocal(VarUint32::deserialize(reader)?.into()),
 				TEELOCAL => TeeLocal(VarUint32::deserialize(reader)?.into()),
 				GETGLOBAL => GetGlobal(VarUint32::deserialize(reader)?.into()),
 				SETGLOBAL => SetGlobal(VarUint32::deserialize(reader)?.into()),
+				#[cfg(feature="reference_types")]
+				TABLE_GET => TableGet(VarUint32::deserialize(reader)?.into()),
+				#[cfg(feature="reference_types")]
+				TABLE_SET => TableSet(VarUint32::deserialize(reader)?.into()),
 
 				I32LOAD => I32Load(MultiMemArg::deserialize(reader)?),
 
@@ -1313,6 +1389,13 @@ impl Deserialize for Instruction {
 				F32REINTERPRETI32 => F32ReinterpretI32,
 				F64REINTERPRETI64 => F64ReinterpretI64,
 
+				#[cfg(feature="reference_types")]
+				REF_NULL => RefNull(TableElementType::deserialize(reader)?),
+				#[cfg(feature="reference_types")]
+				REF_IS_NULL => RefIsNull,
+				#[cfg(feature="reference_types")]
+				REF_FUNC => RefFunc(VarUint32::deserialize(reader)?.into()),
+
 				#[cfg(feature="sign_ext")]
 				I32_EXTEND8_S |
 				I32_EXTEND16_S |
@@ -1348,6 +1431,12 @@ fn deserialize_atomic<R: io::Read>(reader: &mut R) -> Result<Instruction, Error>
 	use self::opcodes::atomics::*;
 
 	let val: u8 = Uint8::deserialize(reader)?.into();
+	if val == ATOMIC_FENCE {
+		let flags: u8 = Uint8::deserialize(reader)?.into();
+		if flags != 0 { return Err(Error::InvalidFenceFlags(flags)); }
+		return Ok(Instruction::Atomics(AtomicFence));
+	}
+
 	let mem = MemArg::deserialize(reader)?;
 	Ok(Instruction::Atomics(match val {
 		ATOMIC_WAKE => AtomicWake(mem),
@@ -1385,6 +1474,14 @@ fn deserialize_atomic<R: io::Read>(reader: &mut R) -> Result<Instruction, Error>
 		I64_ATOMIC_RMW_SUB16U => I64AtomicRmwSub16u(mem),
 		I64_ATOMIC_RMW_SUB32U => I64AtomicRmwSub32u(mem),
 
+		I32_ATOMIC_RMW_AND => I32AtomicRmwAnd(mem),
+		I64_ATOMIC_RMW_AND => I64AtomicRmwAnd(mem),
+		I32_ATOMIC_RMW_AND8U => I32AtomicRmwAnd8u(mem),
+		I32_ATOMIC_RMW_AND16U => I32AtomicRmwAnd16u(mem),
+		I64_ATOMIC_RMW_AND8U => I64AtomicRmwAnd8u(mem),
+		I64_ATOMIC_RMW_AND16U => I64AtomicRmwAnd16u(mem),
+		I64_ATOMIC_RMW_AND32U => I64AtomicRmwAnd32u(mem),
+
 		I32_ATOMIC_RMW_OR => I32AtomicRmwOr(mem),
 		I64_ATOMIC_RMW_OR => I64AtomicRmwOr(mem),
 		I32_ATOMIC_RMW_OR8U => I32AtomicRmwOr8u(mem),
@@ -1620,19 +1717,37 @@ fn deserialize_bulk<R: io::Read>(reader: &mut R) -> Result<Instruction, Error> {
 			MemoryCopy
 		}
 
+		#[cfg(not(feature="reference_types"))]
 		TABLE_INIT => {
 			if u8::from(Uint8::deserialize(reader)?) != 0 {
 				return Err(Error::UnknownOpcode(val))
 			}
-			TableInit(VarUint32::deserialize(reader)?.into())
+			TableInit(VarUint32::deserialize(reader)?.into(), 0)
 		}
+		#[cfg(feature="reference_types")]
+		TABLE_INIT => TableInit(
+			VarUint32::deserialize(reader)?.into(),
+			VarUint32::deserialize(reader)?.into(),
+		),
 		TABLE_DROP => TableDrop(VarUint32::deserialize(reader)?.into()),
+		#[cfg(not(feature="reference_types"))]
 		TABLE_COPY => {
 			if u8::from(Uint8::deserialize(reader)?) != 0 {
 				return Err(Error::UnknownOpcode(val))
 			}
-			TableCopy
+			TableCopy(0, 0)
 		}
+		#[cfg(feature="reference_types")]
+		TABLE_COPY => TableCopy(
+			VarUint32::deserialize(reader)?.into(),
+			VarUint32::deserialize(reader)?.into(),
+		),
+		#[cfg(feature="reference_types")]
+		TABLE_GROW => TableGrow(VarUint32::deserialize(reader)?.into()),
+		#[cfg(feature="reference_types")]
+		TABLE_SIZE => TableSize(VarUint32::deserialize(reader)?.into()),
+		#[cfg(feature="reference_types")]
+		TABLE_FILL => TableFill(VarUint32::deserialize(reader)?.into()),
 
 		_ => return Err(Error::UnknownOpcode(val)),
 	}))
@@ -1644,8 +1759,8 @@ impl Deserialize for MemArg {
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
 		let align = Uint8::deserialize(reader)?;
-		let offset = VarUint32::deserialize(reader)?;
-		Ok(MemArg { align: align.into(), offset: offset.into() })
+		let offset = deserialize_offset(reader)?;
+		Ok(MemArg { align: align.into(), offset })
 	}
 }
 
@@ -1654,7 +1769,7 @@ impl Deserialize for MultiMemArg {
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
 		let align = VarUint32::deserialize(reader)?.into();
-		let offset = VarUint32::deserialize(reader)?.into();
+		let offset = deserialize_offset(reader)?;
 		let memory_index = if (align & (1 << 6)) > 0 {
 			VarUint32::deserialize(reader)?.into()
 		} else {
@@ -1664,6 +1779,16 @@ impl Deserialize for MultiMemArg {
 	}
 }
 
+#[cfg(not(feature="memory64"))]
+fn deserialize_offset<R: io::Read>(reader: &mut R) -> Result<u64, Error> {
+	Ok(u32::from(VarUint32::deserialize(reader)?).into())
+}
+
+#[cfg(feature="memory64")]
+fn deserialize_offset<R: io::Read>(reader: &mut R) -> Result<u64, Error> {
+	Ok(VarUint64::deserialize(reader)?.into())
+}
+
 macro_rules! op {
 	($writer: expr, $byte: expr) => ({
 		let b: u8 = $byte;
@@ -1742,12 +1867,26 @@ impl Serialize for Instruction {
 			Call(index) => op!(writer, CALL, {
 				VarUint32::from(index).serialize(writer)?;
 			}),
-			CallIndirect(index, reserved) => op!(writer, CALLINDIRECT, {
+			#[cfg(not(feature="reference_types"))]
+			CallIndirect(index, table) => {
+				if table != 0 { return Err(Error::InvalidTableReference(table)); }
+				op!(writer, CALLINDIRECT, {
+					VarUint32::from(index).serialize(writer)?;
+					Uint8::from(0).serialize(writer)?;
+				});
+			},
+			#[cfg(feature="reference_types")]
+			CallIndirect(index, table) => op!(writer, CALLINDIRECT, {
 				VarUint32::from(index).serialize(writer)?;
-				Uint8::from(reserved).serialize(writer)?;
+				VarUint32::from(table).serialize(writer)?;
 			}),
 			Drop => op!(writer, DROP),
 			Select => op!(writer, SELECT),
+			#[cfg(feature="reference_types")]
+			TypedSelect(value_type) => op!(writer, TYPED_SELECT, {
+				VarUint32::from(1u32).serialize(writer)?;
+				value_type.serialize(writer)?;
+			}),
 			GetLocal(index) => op!(writer, GETLOCAL, {
 				VarUint32::from(index).serialize(writer)?;
 			}),
@@ -1763,6 +1902,14 @@ impl Serialize for Instruction {
 			SetGlobal(index) => op!(writer, SETGLOBAL, {
 				VarUint32::from(index).serialize(writer)?;
 			}),
+			#[cfg(feature="reference_types")]
+			TableGet(table) => op!(writer, TABLE_GET, {
+				VarUint32::from(table).serialize(writer)?;
+			}),
+			#[cfg(feature="reference_types")]
+			TableSet(table) => op!(writer, TABLE_SET, {
+				VarUint32::from(table).serialize(writer)?;
+			}),
 			I32Load(mem_arg) => op!(writer, I32LOAD, {
 				mem_arg.serialize(writer)?;
 			}),
@@ -1981,6 +2128,17 @@ impl Serialize for Instruction {
 			F32ReinterpretI32 => op!(writer, F32REINTERPRETI32),
 			F64ReinterpretI64 => op!(writer, F64REINTERPRETI64),
 
+			#[cfg(feature="reference_types")]
+			RefNull(ref_type) => op!(writer, REF_NULL, {
+				ref_type.serialize(writer)?;
+			}),
+			#[cfg(feature="reference_types")]
+			RefIsNull => op!(writer, REF_IS_NULL),
+			#[cfg(feature="reference_types")]
+			RefFunc(index) => op!(writer, REF_FUNC, {
+				VarUint32::from(index).serialize(writer)?;
+			}),
+
 			#[cfg(feature="sign_ext")]
 			SignExt(ref a) => match *a {
 				SignExtInstruction::I32Extend8S => op!(writer, sign_ext::I32_EXTEND8_S),
@@ -2004,6 +2162,27 @@ impl Serialize for Instruction {
 	}
 }
 
+impl SerializedSize for Instruction {
+	fn serialized_size(&self) -> usize {
+		match *self {
+			Instruction::BrTable(ref table) => {
+				1 + unsigned_size(table.table.len() as u64)
+					+ table.table.iter().map(|&target| unsigned_size(target.into())).sum::<usize>()
+					+ unsigned_size(table.default.into())
+			},
+			_ => {
+				// Apart from `br_table` the immediates are plain values (or a boxed 16-byte
+				// SIMD operand), so encoding a copy into a counter is cheap. Encoding only
+				// fails for immediates this build cannot represent, and then serializing the
+				// module fails as well.
+				let mut counter = ByteCounter(0);
+				let _ = self.clone().serialize(&mut counter);
+				counter.0
+			},
+		}
+	}
+}
+
 #[cfg(feature="atomics")]
 impl Serialize for AtomicsInstruction {
 	type Error = Error;
@@ -2016,6 +2195,7 @@ impl Serialize for AtomicsInstruction {
 			AtomicWake(m) => atomic!(writer, ATOMIC_WAKE, m),
 			I32AtomicWait(m) => atomic!(writer, I32_ATOMIC_WAIT, m),
 			I64AtomicWait(m) => atomic!(writer, I64_ATOMIC_WAIT, m),
+			AtomicFence => { writer.write(&[ATOMIC_PREFIX, ATOMIC_FENCE, 0x00])?; },
 
 			I32AtomicLoad(m) => atomic!(writer, I32_ATOMIC_LOAD, m),
 			I64AtomicLoad(m) => atomic!(writer, I64_ATOMIC_LOAD, m),
@@ -2275,12 +2455,37 @@ impl Serialize for BulkInstruction {
 			MemoryDrop(seg) => bulk!(writer, MEMORY_DROP, VarUint32::from(seg).serialize(writer)?),
 			MemoryFill => bulk!(writer, MEMORY_FILL, Uint8::from(0).serialize(writer)?),
 			MemoryCopy => bulk!(writer, MEMORY_COPY, Uint8::from(0).serialize(writer)?),
-			TableInit(seg) => bulk!(writer, TABLE_INIT, {
-				Uint8::from(0).serialize(writer)?;
+			#[cfg(not(feature="reference_types"))]
+			TableInit(seg, table) => {
+				if table != 0 { return Err(Error::InvalidTableReference(table)); }
+				bulk!(writer, TABLE_INIT, {
+					Uint8::from(0).serialize(writer)?;
+					VarUint32::from(seg).serialize(writer)?;
+				});
+			},
+			#[cfg(feature="reference_types")]
+			TableInit(seg, table) => bulk!(writer, TABLE_INIT, {
 				VarUint32::from(seg).serialize(writer)?;
+				VarUint32::from(table).serialize(writer)?;
 			}),
 			TableDrop(seg) => bulk!(writer, TABLE_DROP, VarUint32::from(seg).serialize(writer)?),
-			TableCopy => bulk!(writer, TABLE_COPY, Uint8::from(0).serialize(writer)?),
+			#[cfg(not(feature="reference_types"))]
+			TableCopy(dst, src) => {
+				if dst != 0 { return Err(Error::InvalidTableReference(dst)); }
+				if src != 0 { return Err(Error::InvalidTableReference(src)); }
+				bulk!(writer, TABLE_COPY, Uint8::from(0).serialize(writer)?);
+			},
+			#[cfg(feature="reference_types")]
+			TableCopy(dst, src) => bulk!(writer, TABLE_COPY, {
+				VarUint32::from(dst).serialize(writer)?;
+				VarUint32::from(src).serialize(writer)?;
+			}),
+			#[cfg(feature="reference_types")]
+			TableGrow(table) => bulk!(writer, TABLE_GROW, VarUint32::from(table).serialize(writer)?),
+			#[cfg(feature="reference_types")]
+			TableSize(table) => bulk!(writer, TABLE_SIZE, VarUint32::from(table).serialize(writer)?),
+			#[cfg(feature="reference_types")]
+			TableFill(table) => bulk!(writer, TABLE_FILL, VarUint32::from(table).serialize(writer)?),
 		}
 
 		Ok(())
@@ -2293,7 +2498,7 @@ impl Serialize for MemArg {
 
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
 		Uint8::from(self.align).serialize(writer)?;
-		VarUint32::from(self.offset).serialize(writer)?;
+		serialize_offset(self.offset, writer)?;
 		Ok(())
 	}
 }
@@ -2304,16 +2509,27 @@ impl Serialize for MultiMemArg {
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
 		if self.memory_index == 0 {
 			VarUint32::from(self.align).serialize(writer)?;
-			VarUint32::from(self.offset).serialize(writer)?;
+			serialize_offset(self.offset, writer)?;
 		} else {
 			VarUint32::from(self.align | (1 << 6)).serialize(writer)?;
-			VarUint32::from(self.offset).serialize(writer)?;
+			serialize_offset(self.offset, writer)?;
 			VarUint32::from(self.memory_index).serialize(writer)?;
 		}
 		Ok(())
 	}
 }
 
+#[cfg(not(feature="memory64"))]
+fn serialize_offset<W: io::Write>(offset: u64, writer: &mut W) -> Result<(), Error> {
+	let offset = u32::try_from(offset).map_err(|_| Error::InvalidVarUint32)?;
+	VarUint32::from(offset).serialize(writer)
+}
+
+#[cfg(feature="memory64")]
+fn serialize_offset<W: io::Write>(offset: u64, writer: &mut W) -> Result<(), Error> {
+	VarUint64::from(offset).serialize(writer)
+}
+
 macro_rules! fmt_op {
 	($f: expr, $mnemonic: expr) => ({
 		write!($f, "{}", $mnemonic)
@@ -2349,11 +2565,17 @@ impl fmt::Display for Instruction {
 			CallIndirect(index, _) => fmt_op!(f, "call_indirect", index),
 			Drop => fmt_op!(f, "drop"),
 			Select => fmt_op!(f, "select"),
+			#[cfg(feature="reference_types")]
+			TypedSelect(value_type) => write!(f, "select (result {})", value_type),
 			GetLocal(index) => fmt_op!(f, "get_local", index),
 			SetLocal(index) => fmt_op!(f, "set_local", index),
 			TeeLocal(index) => fmt_op!(f, "tee_local", index),
 			GetGlobal(index) => fmt_op!(f, "get_global", index),
 			SetGlobal(index) => fmt_op!(f, "set_global", index),
+			#[cfg(feature="reference_types")]
+			TableGet(index) => fmt_op!(f, "table.get", index),
+			#[cfg(feature="reference_types")]
+			TableSet(index) => fmt_op!(f, "table.set", index),
 
 			I32Load(MultiMemArg { offset: 0, memory_index, .. })if memory_index > 0 => write!(f, "i32.load (memory {})", memory_index),
 			I32Load(MultiMemArg { offset, memory_index, .. })if memory_index > 0 => write!(f, "i32.load (memory {}) offset={}", memory_index, offset),
@@ -2615,6 +2837,15 @@ impl fmt::Display for Instruction {
 			F32ReinterpretI32 => write!(f, "f32.reinterpret/i32"),
 			F64ReinterpretI64 => write!(f, "f64.reinterpret/i64"),
 
+			#[cfg(feature="reference_types")]
+			RefNull(TableElementType::AnyFunc) => write!(f, "ref.null func"),
+			#[cfg(feature="reference_types")]
+			RefNull(TableElementType::ExternRef) => write!(f, "ref.null extern"),
+			#[cfg(feature="reference_types")]
+			RefIsNull => write!(f, "ref.is_null"),
+			#[cfg(feature="reference_types")]
+			RefFunc(index) => fmt_op!(f, "ref.func", index),
+
 			#[cfg(feature="sign_ext")]
 			SignExt(ref i) => match i {
 				SignExtInstruction::I32Extend8S => write!(f, "i32.extend8_s"),
@@ -2645,6 +2876,7 @@ impl fmt::Display for AtomicsInstruction {
 			AtomicWake(_) => write!(f, "atomic.wake"),
 			I32AtomicWait(_) => write!(f, "i32.atomic.wait"),
 			I64AtomicWait(_) => write!(f, "i64.atomic.wait"),
+			AtomicFence => write!(f, "atomic.fence"),
 
 			I32AtomicLoad(_) => write!(f, "i32.atomic.load"),
 			I64AtomicLoad(_) => write!(f, "i64.atomic.load"),
@@ -2891,9 +3123,15 @@ impl fmt::Display for BulkInstruction {
 			MemoryDrop(_) => write!(f, "memory.drop"),
 			MemoryFill => write!(f, "memory.fill"),
 			MemoryCopy => write!(f, "memory.copy"),
-			TableInit(_) => write!(f, "table.init"),
+			TableInit(_, _) => write!(f, "table.init"),
 			TableDrop(_) => write!(f, "table.drop"),
-			TableCopy => write!(f, "table.copy"),
+			TableCopy(_, _) => write!(f, "table.copy"),
+			#[cfg(feature="reference_types")]
+			TableGrow(_) => write!(f, "table.grow"),
+			#[cfg(feature="reference_types")]
+			TableSize(_) => write!(f, "table.size"),
+			#[cfg(feature="reference_types")]
+			TableFill(_) => write!(f, "table.fill"),
 		}
 	}
 }
@@ -2910,6 +3148,12 @@ impl Serialize for Instructions {
 	}
 }
 
+impl SerializedSize for Instructions {
+	fn serialized_size(&self) -> usize {
+		self.0.iter().map(SerializedSize::serialized_size).sum()
+	}
+}
+
 impl Serialize for InitExpr {
 	type Error = Error;
 
@@ -2922,6 +3166,12 @@ impl Serialize for InitExpr {
 	}
 }
 
+impl SerializedSize for InitExpr {
+	fn serialized_size(&self) -> usize {
+		self.0.iter().map(SerializedSize::serialized_size).sum()
+	}
+}
+
 #[test]
 fn ifelse() {
 	// see if-else.wast/if-else.wasm
@@ -2959,7 +3209,7 @@ fn display() {
 
 #[test]
 fn size_off() {
-	assert!(::std::mem::size_of::<Instruction>() <= 24);
+	assert!(::std::mem::size_of::<Instruction>() <= 32);
 }
 
 #[test]
@@ -2971,3 +3221,150 @@ fn instructions_hashset() {
 		vec![Call(1), Block(Value(ValueType::I32)), Drop].into_iter().collect();
 	assert_eq!(set.contains(&Drop), true)
 }
+
+#[test]
+fn unknown_opcode() {
+	match super::deserialize_buffer::<Instruction>(&[0xff]) {
+		Err(Error::UnknownOpcode(0xff)) => {},
+		other => panic!("expected UnknownOpcode, got {:?}", other),
+	}
+}
+
+#[cfg(feature="atomics")]
+#[test]
+fn atomics_roundtrip() {
+	// every opcode in 0xFE 0x00..=0x4E except atomic.fence takes a memarg
+	for opcode in (0x00u8..=0x02).chain(0x10..=0x4e) {
+		let bytes = [0xfe, opcode, 0x02, 0x08];
+		let instruction = super::deserialize_buffer::<Instruction>(&bytes)
+			.unwrap_or_else(|_| panic!("atomic opcode 0x{:02x} should decode", opcode));
+		assert_eq!(super::serialize(instruction).expect("serialization to succeed"), bytes);
+	}
+
+	// atomic.fence takes a single reserved zero byte instead
+	let bytes = [0xfe, 0x03, 0x00];
+	let instruction = super::deserialize_buffer::<Instruction>(&bytes).expect("atomic.fence to decode");
+	assert_eq!(instruction, Instruction::Atomics(AtomicsInstruction::AtomicFence));
+	assert_eq!(format!("{}", instruction), "atomic.fence");
+	assert_eq!(super::serialize(instruction).expect("serialization to succeed"), bytes);
+	match super::deserialize_buffer::<Instruction>(&[0xfe, 0x03, 0x01]) {
+		Err(Error::InvalidFenceFlags(1)) => {},
+		other => panic!("expected InvalidFenceFlags, got {:?}", other),
+	}
+}
+
+#[cfg(feature="reference_types")]
+#[test]
+fn typed_select() {
+	use super::{deserialize_buffer, serialize};
+
+	let bytes = [0x1c, 0x01, 0x7e];
+	let instruction = deserialize_buffer::<Instruction>(&bytes).expect("typed select to decode");
+	assert_eq!(instruction, Instruction::TypedSelect(ValueType::I64));
+	assert_eq!(format!("{}", instruction), "select (result i64)");
+	assert_eq!(serialize(instruction).expect("serialization to succeed"), bytes);
+
+	let bytes = [0x1c, 0x01, 0x70];
+	let instruction = deserialize_buffer::<Instruction>(&bytes).expect("typed select to decode");
+	assert_eq!(instruction, Instruction::TypedSelect(ValueType::FuncRef));
+	assert_eq!(format!("{}", instruction), "select (result funcref)");
+	assert_eq!(serialize(instruction).expect("serialization to succeed"), bytes);
+
+	let instruction = deserialize_buffer::<Instruction>(&[0x1c, 0x01, 0x6f]).expect("typed select to decode");
+	assert_eq!(instruction, Instruction::TypedSelect(ValueType::ExternRef));
+
+	assert_eq!(deserialize_buffer::<Instruction>(&[0x1b]).expect("select to decode"), Instruction::Select);
+	match deserialize_buffer::<Instruction>(&[0x1c, 0x02, 0x7e, 0x7e]) {
+		Err(Error::InvalidSelectArity(2)) => {},
+		other => panic!("expected InvalidSelectArity, got {:?}", other),
+	}
+}
+
+#[cfg(feature="reference_types")]
+#[test]
+fn reference_instructions() {
+	use super::{deserialize_buffer, serialize};
+	use self::Instruction::*;
+
+	let cases: &[(&[u8], Instruction, &str)] = &[
+		(&[0x25, 0x01], TableGet(1), "table.get 1"),
+		(&[0x26, 0x00], TableSet(0), "table.set 0"),
+		(&[0xfc, 0x0f, 0x02], Bulk(BulkInstruction::TableGrow(2)), "table.grow"),
+		(&[0xfc, 0x10, 0x00], Bulk(BulkInstruction::TableSize(0)), "table.size"),
+		(&[0xfc, 0x11, 0x03], Bulk(BulkInstruction::TableFill(3)), "table.fill"),
+		(&[0xfc, 0x0c, 0x04, 0x01], Bulk(BulkInstruction::TableInit(4, 1)), "table.init"),
+		(&[0xfc, 0x0e, 0x01, 0x02], Bulk(BulkInstruction::TableCopy(1, 2)), "table.copy"),
+		(&[0x11, 0x03, 0x01], CallIndirect(3, 1), "call_indirect 3"),
+		(&[0xd0, 0x70], RefNull(TableElementType::AnyFunc), "ref.null func"),
+		(&[0xd0, 0x6f], RefNull(TableElementType::ExternRef), "ref.null extern"),
+		(&[0xd1], RefIsNull, "ref.is_null"),
+		(&[0xd2, 0x05], RefFunc(5), "ref.func 5"),
+	];
+	for &(bytes, ref instruction, text) in cases {
+		assert_eq!(&deserialize_buffer::<Instruction>(bytes).expect("table instruction to decode"), instruction);
+		assert_eq!(format!("{}", instruction), text);
+		assert_eq!(serialize(instruction.clone()).expect("serialization to succeed"), bytes);
+	}
+}
+
+#[cfg(feature="memory64")]
+#[test]
+fn memory64_offset() {
+	use super::{deserialize_buffer, serialize};
+
+	// i64.load offset=2^32
+	let bytes = [0x29, 0x03, 0x80, 0x80, 0x80, 0x80, 0x10];
+	let instruction = deserialize_buffer::<Instruction>(&bytes).expect("memory64 load to decode");
+	assert_eq!(instruction, Instruction::I64Load(MultiMemArg { offset: 1 << 32, align: 3, memory_index: 0 }));
+	assert_eq!(format!("{}", instruction), "i64.load offset=4294967296");
+	assert_eq!(serialize(instruction).expect("serialization to succeed"), bytes);
+}
+
+#[cfg(not(feature="memory64"))]
+#[test]
+fn oversized_offset_rejected() {
+	use super::serialize;
+
+	let instruction = Instruction::I64Load(MultiMemArg { offset: 1 << 32, align: 3, memory_index: 0 });
+	match serialize(instruction) {
+		Err(Error::InvalidVarUint32) => {},
+		other => panic!("expected InvalidVarUint32, got {:?}", other),
+	}
+}
+
+#[cfg(not(feature="reference_types"))]
+#[test]
+fn table_zero_only() {
+	use super::{deserialize_buffer, serialize};
+
+	let bytes = [0x11, 0x03, 0x00];
+	let instruction = deserialize_buffer::<Instruction>(&bytes).expect("call_indirect to decode");
+	assert_eq!(instruction, Instruction::CallIndirect(3, 0));
+	assert_eq!(serialize(instruction).expect("serialization to succeed"), bytes);
+
+	match serialize(Instruction::CallIndirect(3, 1)) {
+		Err(Error::InvalidTableReference(1)) => {},
+		other => panic!("expected InvalidTableReference, got {:?}", other),
+	}
+}
+
+#[cfg(all(feature="bulk", not(feature="reference_types")))]
+#[test]
+fn bulk_table_zero_only() {
+	use super::{deserialize_buffer, serialize};
+
+	let cases: &[(&[u8], BulkInstruction)] = &[
+		(&[0xfc, 0x0c, 0x00, 0x04], BulkInstruction::TableInit(4, 0)),
+		(&[0xfc, 0x0e, 0x00], BulkInstruction::TableCopy(0, 0)),
+	];
+	for &(bytes, ref instruction) in cases {
+		let instruction = Instruction::Bulk(instruction.clone());
+		assert_eq!(deserialize_buffer::<Instruction>(bytes).expect("table instruction to decode"), instruction);
+		assert_eq!(serialize(instruction).expect("serialization to succeed"), bytes);
+	}
+
+	match serialize(Instruction::Bulk(BulkInstruction::TableCopy(0, 2))) {
+		Err(Error::InvalidTableReference(2)) => {},
+		other => panic!("expected InvalidTableReference, got {:?}", other),
+	}
+}
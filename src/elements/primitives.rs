@@ -1,6 +1,6 @@
 use alloc::{string::String, vec::Vec};
 use crate::{io, elements};
-use super::{Error, Deserialize, Serialize};
+use super::{Error, Deserialize, Serialize, SerializedSize, leb128};
 
 
 #[cfg(feature = "reduced-stack-buffer")]
@@ -43,24 +43,7 @@ impl Deserialize for VarUint32 {
 	type Error = Error;
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
-		let mut res = 0;
-		let mut shift = 0;
-		let mut u8buf = [0u8; 1];
-		loop {
-			if shift > 31 { return Err(Error::InvalidVarUint32); }
-
-			reader.read(&mut u8buf)?;
-			let b = u8buf[0] as u32;
-			res |= (b & 0x7f).checked_shl(shift).ok_or(Error::InvalidVarUint32)?;
-			shift += 7;
-			if (b >> 7) == 0 {
-				if shift >= 32 && (b as u8).leading_zeros() < 4 {
-					return Err(Error::InvalidVarInt32);
-				}
-				break;
-			}
-		}
-		Ok(VarUint32(res))
+		Ok(VarUint32(leb128::read_u32(reader)?))
 	}
 }
 
@@ -68,19 +51,7 @@ impl Serialize for VarUint32 {
 	type Error = Error;
 
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
-		let mut buf = [0u8; 1];
-		let mut v = self.0;
-		loop {
-			buf[0] = (v & 0b0111_1111) as u8;
-			v >>= 7;
-			if v > 0 {
-				buf[0] |= 0b1000_0000;
-			}
-			writer.write(&buf[..])?;
-			if v == 0 { break; }
-		}
-
-		Ok(())
+		leb128::write_u32(writer, self.0)
 	}
 }
 
@@ -99,24 +70,7 @@ impl Deserialize for VarUint64 {
 	type Error = Error;
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
-		let mut res = 0;
-		let mut shift = 0;
-		let mut u8buf = [0u8; 1];
-		loop {
-			if shift > 63 { return Err(Error::InvalidVarUint64); }
-
-			reader.read(&mut u8buf)?;
-			let b = u8buf[0] as u64;
-			res |= (b & 0x7f).checked_shl(shift).ok_or(Error::InvalidVarUint64)?;
-			shift += 7;
-			if (b >> 7) == 0 {
-				if shift >= 64 && (b as u8).leading_zeros() < 7 {
-					return Err(Error::InvalidVarInt64);
-				}
-				break;
-			}
-		}
-		Ok(VarUint64(res))
+		Ok(VarUint64(leb128::read_u64(reader)?))
 	}
 }
 
@@ -124,19 +78,7 @@ impl Serialize for VarUint64 {
 	type Error = Error;
 
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
-		let mut buf = [0u8; 1];
-		let mut v = self.0;
-		loop {
-			buf[0] = (v & 0b0111_1111) as u8;
-			v >>= 7;
-			if v > 0 {
-				buf[0] |= 0b1000_0000;
-			}
-			writer.write(&buf[..])?;
-			if v == 0 { break; }
-		}
-
-		Ok(())
+		leb128::write_u64(writer, self.0)
 	}
 }
 
@@ -286,33 +228,7 @@ impl Deserialize for VarInt32 {
 	type Error = Error;
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
-		let mut res = 0;
-		let mut shift = 0;
-		let mut u8buf = [0u8; 1];
-		loop {
-			if shift > 31 { return Err(Error::InvalidVarInt32); }
-			reader.read(&mut u8buf)?;
-			let b = u8buf[0];
-
-			res |= ((b & 0x7f) as i32).checked_shl(shift).ok_or(Error::InvalidVarInt32)?;
-
-			shift += 7;
-			if (b >> 7) == 0 {
-				if shift < 32 && b & 0b0100_0000 == 0b0100_0000 {
-					res |= (1i32 << shift).wrapping_neg();
-				} else if shift >= 32 && b & 0b0100_0000 == 0b0100_0000 {
-					if (!(b | 0b1000_0000)).leading_zeros() < 5 {
-						return Err(Error::InvalidVarInt32);
-					}
-				} else if shift >= 32 && b & 0b0100_0000 == 0 {
-					if b.leading_zeros() < 5 {
-						return Err(Error::InvalidVarInt32);
-					}
-				}
-				break;
-			}
-		}
-		Ok(VarInt32(res))
+		Ok(VarInt32(leb128::read_i32(reader)?))
 	}
 }
 
@@ -320,22 +236,7 @@ impl Serialize for VarInt32 {
 	type Error = Error;
 
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
-		let mut buf = [0u8; 1];
-		let mut v = self.0;
-		let mut more = true;
-		while more {
-			buf[0] = (v & 0b0111_1111) as u8;
-			v >>= 7;
-			if (v == 0 && buf[0] & 0b0100_0000 == 0) || (v == -1 && buf[0] & 0b0100_0000 == 0b0100_0000)  {
-				more = false
-			} else {
-				buf[0] |= 0b1000_0000
-			}
-
-			writer.write(&buf[..])?;
-		}
-
-		Ok(())
+		leb128::write_i32(writer, self.0)
 	}
 }
 
@@ -359,32 +260,7 @@ impl Deserialize for VarInt64 {
 	type Error = Error;
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
-		let mut res = 0i64;
-		let mut shift = 0;
-		let mut u8buf = [0u8; 1];
-
-		loop {
-			if shift > 63 { return Err(Error::InvalidVarInt64); }
-			reader.read(&mut u8buf)?;
-			let b = u8buf[0];
-
-			res |= ((b & 0x7f) as i64).checked_shl(shift).ok_or(Error::InvalidVarInt64)?;
-
-			shift += 7;
-			if (b >> 7) == 0 {
-				if shift < 64 && b & 0b0100_0000 == 0b0100_0000 {
-					res |= (1i64 << shift).wrapping_neg();
-				} else if shift >= 64 && b & 0b0100_0000 == 0b0100_0000 {
-					if (b | 0b1000_0000) as i8 != -1 {
-						return Err(Error::InvalidVarInt64);
-					}
-				} else if shift >= 64 && b != 0 {
-					return Err(Error::InvalidVarInt64);
-				}
-				break;
-			}
-		}
-		Ok(VarInt64(res))
+		Ok(VarInt64(leb128::read_i64(reader)?))
 	}
 }
 
@@ -392,22 +268,7 @@ impl Serialize for VarInt64 {
 	type Error = Error;
 
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
-		let mut buf = [0u8; 1];
-		let mut v = self.0;
-		let mut more = true;
-		while more {
-			buf[0] = (v & 0b0111_1111) as u8;
-			v >>= 7;
-			if (v == 0 && buf[0] & 0b0100_0000 == 0) || (v == -1 && buf[0] & 0b0100_0000 == 0b0100_0000)  {
-				more = false
-			} else {
-				buf[0] |= 0b1000_0000
-			}
-
-			writer.write(&buf[..])?;
-		}
-
-		Ok(())
+		leb128::write_i64(writer, self.0)
 	}
 }
 
@@ -545,6 +406,12 @@ impl Serialize for String {
 	}
 }
 
+impl SerializedSize for String {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(self.len())
+	}
+}
+
 /// List for reading sequence of elements typed `T`, given
 /// they are preceded by length (serialized as VarUint32).
 #[derive(Debug, Clone)]
@@ -560,12 +427,46 @@ impl<T: Deserialize> Deserialize for CountedList<T> where T::Error: From<Error>
 
 	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
 		let count: usize = VarUint32::deserialize(reader)?.into();
+		check_vector_length(reader, count)?;
 		let mut result = Vec::new();
 		for _ in 0..count { result.push(T::deserialize(reader)?); }
 		Ok(CountedList(result))
 	}
 }
 
+/// Reject a declared vector length that cannot fit in what is left of `reader`.
+///
+/// Every element takes at least one byte, so this bounds any allocation made for
+/// the vector by the size of the input. Nothing is checked if the reader cannot
+/// tell how much input is left.
+pub(crate) fn check_vector_length<R: io::Read>(reader: &R, len: usize) -> Result<(), Error> {
+	match reader.remaining() {
+		Some(remaining) if len > remaining => Err(Error::InvalidVectorLength),
+		_ => Ok(()),
+	}
+}
+
+/// Serialized length of `len` bytes preceded by their length, as written by
+/// `CountedWriter` or for strings.
+pub(crate) fn prefixed_size(len: usize) -> usize {
+	leb128::unsigned_size(len as u64) + len
+}
+
+/// Serialized length of `items` as written by `CountedListWriter`.
+pub(crate) fn counted_list_size<T: SerializedSize>(items: &[T]) -> usize {
+	leb128::unsigned_size(items.len() as u64) + items.iter().map(SerializedSize::serialized_size).sum::<usize>()
+}
+
+/// Writer that discards the data and only counts the bytes written.
+pub(crate) struct ByteCounter(pub usize);
+
+impl io::Write for ByteCounter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<()> {
+		self.0 += buf.len();
+		Ok(())
+	}
+}
+
 /// Helper struct to write payload which is preceded by
 /// it's own length in bytes.
 #[derive(Debug)]
@@ -906,4 +807,13 @@ mod tests {
 		let v3: i8 = (*vars.get(1).unwrap()).into();
 		assert_eq!(-0x03i8, v3);
 	}
+
+	#[test]
+	fn counted_list_length_exceeds_input() {
+		// Declares u32::MAX entries but carries a single byte.
+		match deserialize_buffer::<CountedList<VarInt7>>(&[0xff, 0xff, 0xff, 0xff, 0x0f, 0x7f]) {
+			Err(Error::InvalidVectorLength) => {},
+			other => panic!("expected InvalidVectorLength, got {:?}", other.map(|l| l.into_inner().len())),
+		}
+	}
 }
@@ -0,0 +1,193 @@
+use alloc::{string::String, vec::Vec};
+use crate::io;
+
+use super::{CountedList, CountedListWriter, Deserialize, Error, Serialize};
+
+/// Field listing the source languages of the module.
+const FIELD_LANGUAGE: &str = "language";
+/// Field listing the tools that processed the module.
+const FIELD_PROCESSED_BY: &str = "processed-by";
+/// Field listing the SDKs used to build the module.
+const FIELD_SDK: &str = "sdk";
+
+/// Toolchain information from the "producers" custom section.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProducersSection {
+	fields: Vec<ProducersField>,
+}
+
+impl ProducersSection {
+	/// New producers section with the given fields.
+	pub fn new(fields: Vec<ProducersField>) -> Self {
+		ProducersSection { fields }
+	}
+
+	/// All fields, in the order they appear, including ones not defined by the tool conventions.
+	pub fn fields(&self) -> &[ProducersField] {
+		&self.fields
+	}
+
+	/// All fields (mutable).
+	pub fn fields_mut(&mut self) -> &mut Vec<ProducersField> {
+		&mut self.fields
+	}
+
+	/// Values of the field with the given name, or an empty slice if it is absent.
+	pub fn field(&self, name: &str) -> &[ProducerValue] {
+		self.fields.iter()
+			.find(|f| f.name == name)
+			.map(|f| &f.values[..])
+			.unwrap_or(&[])
+	}
+
+	/// Source languages, e.g. `Rust`.
+	pub fn language(&self) -> &[ProducerValue] {
+		self.field(FIELD_LANGUAGE)
+	}
+
+	/// Tools that processed the module, e.g. `rustc` or `LLVM`.
+	pub fn processed_by(&self) -> &[ProducerValue] {
+		self.field(FIELD_PROCESSED_BY)
+	}
+
+	/// SDKs used to build the module, e.g. `Emscripten`.
+	pub fn sdk(&self) -> &[ProducerValue] {
+		self.field(FIELD_SDK)
+	}
+}
+
+impl Deserialize for ProducersSection {
+	type Error = Error;
+
+	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+		Ok(ProducersSection { fields: CountedList::deserialize(reader)?.into_inner() })
+	}
+}
+
+impl Serialize for ProducersSection {
+	type Error = Error;
+
+	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
+		CountedListWriter(self.fields.len(), self.fields).serialize(writer)
+	}
+}
+
+/// Named field of the producers section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProducersField {
+	name: String,
+	values: Vec<ProducerValue>,
+}
+
+impl ProducersField {
+	/// New field with the given name and values.
+	pub fn new(name: String, values: Vec<ProducerValue>) -> Self {
+		ProducersField { name, values }
+	}
+
+	/// Name of the field.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Values of the field.
+	pub fn values(&self) -> &[ProducerValue] {
+		&self.values
+	}
+
+	/// Values of the field (mutable).
+	pub fn values_mut(&mut self) -> &mut Vec<ProducerValue> {
+		&mut self.values
+	}
+}
+
+impl Deserialize for ProducersField {
+	type Error = Error;
+
+	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+		let name = String::deserialize(reader)?;
+		let values = CountedList::deserialize(reader)?.into_inner();
+		Ok(ProducersField { name, values })
+	}
+}
+
+impl Serialize for ProducersField {
+	type Error = Error;
+
+	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
+		self.name.serialize(writer)?;
+		CountedListWriter(self.values.len(), self.values).serialize(writer)
+	}
+}
+
+/// Name and version of a single producer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProducerValue {
+	name: String,
+	version: String,
+}
+
+impl ProducerValue {
+	/// New producer value.
+	pub fn new(name: String, version: String) -> Self {
+		ProducerValue { name, version }
+	}
+
+	/// Name of the producer.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Version of the producer. May be empty.
+	pub fn version(&self) -> &str {
+		&self.version
+	}
+}
+
+impl Deserialize for ProducerValue {
+	type Error = Error;
+
+	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+		let name = String::deserialize(reader)?;
+		let version = String::deserialize(reader)?;
+		Ok(ProducerValue { name, version })
+	}
+}
+
+impl Serialize for ProducerValue {
+	type Error = Error;
+
+	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
+		self.name.serialize(writer)?;
+		self.version.serialize(writer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::{deserialize_buffer, serialize};
+	use super::*;
+
+	fn value(name: &str, version: &str) -> ProducerValue {
+		ProducerValue::new(name.into(), version.into())
+	}
+
+	#[test]
+	fn roundtrip() {
+		let section = ProducersSection::new(vec![
+			ProducersField::new(FIELD_LANGUAGE.into(), vec![value("Rust", "")]),
+			ProducersField::new(FIELD_PROCESSED_BY.into(), vec![value("rustc", "1.50.0"), value("LLVM", "11.0.1")]),
+			ProducersField::new("x-custom".into(), vec![value("tool", "0.1")]),
+		]);
+
+		let bytes = serialize(section.clone()).expect("serialization to succeed");
+		let parsed: ProducersSection = deserialize_buffer(&bytes).expect("deserialization to succeed");
+		assert_eq!(parsed, section);
+
+		assert_eq!(parsed.language(), &[value("Rust", "")]);
+		assert_eq!(parsed.processed_by()[1].name(), "LLVM");
+		assert_eq!(parsed.processed_by()[1].version(), "11.0.1");
+		assert!(parsed.sdk().is_empty());
+		assert_eq!(parsed.field("x-custom"), &[value("tool", "0.1")]);
+	}
+}
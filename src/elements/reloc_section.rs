@@ -1,7 +1,12 @@
 use alloc::{string::String, vec::Vec};
 use crate::io;
 
-use super::{CountedList, CountedListWriter, CountedWriter, Deserialize, Error, Serialize, VarInt32, VarUint32, VarUint7};
+use super::{
+	CountedList, CountedListWriter, CountedWriter, Deserialize, Error, Serialize, SerializedSize,
+	VarInt32, VarUint32, VarUint7,
+};
+use super::leb128::{signed_size, unsigned_size};
+use super::primitives::{counted_list_size, prefixed_size};
 
 const FUNCTION_INDEX_LEB: u8 = 0;
 const TABLE_INDEX_SLEB: u8 = 1;
@@ -120,6 +125,17 @@ impl Serialize for RelocSection {
 	}
 }
 
+impl SerializedSize for RelocSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(
+			self.name.serialized_size()
+				+ unsigned_size(self.section_id.into())
+				+ self.relocation_section_name.as_ref().map_or(0, SerializedSize::serialized_size)
+				+ counted_list_size(&self.entries)
+		)
+	}
+}
+
 /// Relocation entry.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum RelocationEntry {
@@ -319,6 +335,23 @@ impl Serialize for RelocationEntry {
 	}
 }
 
+impl SerializedSize for RelocationEntry {
+	fn serialized_size(&self) -> usize {
+		let (offset, index, addend) = match *self {
+			RelocationEntry::FunctionIndexLeb { offset, index } |
+			RelocationEntry::TableIndexSleb { offset, index } |
+			RelocationEntry::TableIndexI32 { offset, index } |
+			RelocationEntry::TypeIndexLeb { offset, index } |
+			RelocationEntry::GlobalIndexLeb { offset, index } => (offset, index, None),
+
+			RelocationEntry::MemoryAddressLeb { offset, index, addend } |
+			RelocationEntry::MemoryAddressSleb { offset, index, addend } |
+			RelocationEntry::MemoryAddressI32 { offset, index, addend } => (offset, index, Some(addend)),
+		};
+		1 + unsigned_size(offset.into()) + unsigned_size(index.into()) + addend.map_or(0, |a| signed_size(a.into()))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::super::{Section, deserialize_file};
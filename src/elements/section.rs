@@ -19,27 +19,32 @@ use super::{
 	CountedWriter,
 	CountedListWriter,
 	External,
+	SerializedSize,
 	serialize,
 };
 
+use super::primitives::{check_vector_length, counted_list_size, prefixed_size};
+use super::leb128::unsigned_size;
 use super::types::Type;
 use super::name_section::NameSection;
 use super::reloc_section::RelocSection;
 
 #[cfg(feature = "reduced-stack-buffer")]
-const ENTRIES_BUFFER_LENGTH: usize = 256;
+pub(crate) const ENTRIES_BUFFER_LENGTH: usize = 256;
 
 #[cfg(not(feature = "reduced-stack-buffer"))]
-const ENTRIES_BUFFER_LENGTH: usize = 16384;
+pub(crate) const ENTRIES_BUFFER_LENGTH: usize = 16384;
 
 /// Section in the WebAssembly module.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Section {
 	/// Section is unparsed.
+	///
+	/// Produced by `deserialize_buffer_lenient` for section ids this crate does not know.
 	Unparsed {
 		/// id of the unparsed section.
 		id: u8,
-		/// raw bytes of the unparsed section.
+		/// raw bytes of the unparsed section, starting with its length prefix.
 		payload: Vec<u8>,
 	},
 	/// Custom section (`id=0`).
@@ -80,10 +85,10 @@ pub enum Section {
 	Reloc(RelocSection),
 }
 
-impl Deserialize for Section {
-	type Error = Error;
-
-	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+impl Section {
+	/// Deserialize a section. In lenient mode a section with an unknown id is kept as
+	/// `Section::Unparsed` instead of failing with `Error::InvalidSectionId`.
+	pub(crate) fn deserialize_with<R: io::Read>(reader: &mut R, lenient: bool) -> Result<Self, Error> {
 		let id = match VarUint7::deserialize(reader) {
 			// todo: be more selective detecting no more section
 			Err(_) => { return Err(Error::UnexpectedEof); },
@@ -137,6 +142,9 @@ impl Deserialize for Section {
 					section_reader.close()?;
 					Section::DataCount(count.into())
 				},
+				id if lenient => {
+					Section::Unparsed { id, payload: read_raw_payload(reader)? }
+				},
 				invalid_id => {
 					return Err(Error::InvalidSectionId(invalid_id))
 				},
@@ -145,6 +153,30 @@ impl Deserialize for Section {
 	}
 }
 
+impl Deserialize for Section {
+	type Error = Error;
+
+	fn deserialize<R: io::Read>(reader: &mut R) -> Result<Self, Self::Error> {
+		Section::deserialize_with(reader, false)
+	}
+}
+
+/// Length prefix and contents of a section, exactly as encoded.
+fn read_raw_payload<R: io::Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+	// The length prefix is copied rather than re-encoded, since it may be padded.
+	let mut payload = Vec::new();
+	let mut byte = [0u8; 1];
+	loop {
+		reader.read(&mut byte)?;
+		payload.push(byte[0]);
+		if byte[0] & 0x80 == 0 || payload.len() == 5 { break; }
+	}
+	let length: usize = VarUint32::deserialize(&mut io::Cursor::new(&payload[..]))?.into();
+	check_vector_length(reader, length)?;
+	payload.extend(buffered_read!(ENTRIES_BUFFER_LENGTH, length, reader));
+	Ok(payload)
+}
+
 impl Serialize for Section {
 	type Error = Error;
 
@@ -227,6 +259,31 @@ impl Serialize for Section {
 	}
 }
 
+impl SerializedSize for Section {
+	fn serialized_size(&self) -> usize {
+		// Every section starts with a single byte id.
+		1 + match *self {
+			Section::Custom(ref custom_section) => custom_section.serialized_size(),
+			// The payload already starts with its length.
+			Section::Unparsed { ref payload, .. } => payload.len(),
+			Section::Type(ref type_section) => type_section.serialized_size(),
+			Section::Import(ref import_section) => import_section.serialized_size(),
+			Section::Function(ref function_section) => function_section.serialized_size(),
+			Section::Table(ref table_section) => table_section.serialized_size(),
+			Section::Memory(ref memory_section) => memory_section.serialized_size(),
+			Section::Global(ref global_section) => global_section.serialized_size(),
+			Section::Export(ref export_section) => export_section.serialized_size(),
+			Section::Start(index) => prefixed_size(unsigned_size(index.into())),
+			Section::DataCount(count) => prefixed_size(unsigned_size(count.into())),
+			Section::Element(ref element_section) => element_section.serialized_size(),
+			Section::Code(ref code_section) => code_section.serialized_size(),
+			Section::Data(ref data_section) => data_section.serialized_size(),
+			Section::Name(ref name_section) => prefixed_size(prefixed_size("name".len()) + name_section.serialized_size()),
+			Section::Reloc(ref reloc_section) => reloc_section.serialized_size(),
+		}
+	}
+}
+
 impl Section {
 	pub(crate) fn order(&self) -> u8 {
 		match *self {
@@ -285,6 +342,10 @@ impl io::Read for SectionReader {
 		self.cursor.read(buf)?;
 		Ok(())
 	}
+
+	fn remaining(&self) -> Option<usize> {
+		self.cursor.remaining()
+	}
 }
 
 fn read_entries<R: io::Read, T: Deserialize<Error=elements::Error>>(reader: &mut R)
@@ -357,6 +418,12 @@ impl Serialize for CustomSection {
 	}
 }
 
+impl SerializedSize for CustomSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(self.name.serialized_size() + self.payload.len())
+	}
+}
+
 /// Section with type declarations.
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct TypeSection(Vec<Type>);
@@ -402,6 +469,12 @@ impl Serialize for TypeSection {
 	}
 }
 
+impl SerializedSize for TypeSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Section of the imports definition.
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct ImportSection(Vec<ImportEntry>);
@@ -461,6 +534,12 @@ impl Serialize for ImportSection {
 	}
 }
 
+impl SerializedSize for ImportSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Section with function signatures definition.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct FunctionSection(Vec<Func>);
@@ -506,6 +585,12 @@ impl Serialize for FunctionSection {
 	}
 }
 
+impl SerializedSize for FunctionSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Section with table definition (currently only one is allowed).
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct TableSection(Vec<TableType>);
@@ -551,6 +636,12 @@ impl Serialize for TableSection {
 	}
 }
 
+impl SerializedSize for TableSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Section with table definition (currently only one entry is allowed).
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct MemorySection(Vec<MemoryType>);
@@ -596,6 +687,12 @@ impl Serialize for MemorySection {
 	}
 }
 
+impl SerializedSize for MemorySection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Globals definition section.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct GlobalSection(Vec<GlobalEntry>);
@@ -641,6 +738,12 @@ impl Serialize for GlobalSection {
 	}
 }
 
+impl SerializedSize for GlobalSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// List of exports definition.
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct ExportSection(Vec<ExportEntry>);
@@ -686,6 +789,12 @@ impl Serialize for ExportSection {
 	}
 }
 
+impl SerializedSize for ExportSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Section with function bodies of the module.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct CodeSection(Vec<FuncBody>);
@@ -731,6 +840,12 @@ impl Serialize for CodeSection {
 	}
 }
 
+impl SerializedSize for CodeSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Element entries section.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct ElementSection(Vec<ElementSegment>);
@@ -776,6 +891,12 @@ impl Serialize for ElementSection {
 	}
 }
 
+impl SerializedSize for ElementSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 /// Data entries definitions.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct DataSection(Vec<DataSegment>);
@@ -821,6 +942,12 @@ impl Serialize for DataSection {
 	}
 }
 
+impl SerializedSize for DataSection {
+	fn serialized_size(&self) -> usize {
+		prefixed_size(counted_list_size(&self.0))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 
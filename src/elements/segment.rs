@@ -1,6 +1,11 @@
 use alloc::vec::Vec;
 use crate::io;
-use super::{Deserialize, Serialize, Error, VarUint32, CountedList, InitExpr, CountedListWriter};
+use super::{
+	Deserialize, Serialize, SerializedSize, Error, VarUint32, CountedList, InitExpr,
+	CountedListWriter,
+};
+use super::leb128::unsigned_size;
+use super::primitives::prefixed_size;
 
 #[cfg(feature="bulk")]
 const FLAG_MEMZERO: u32 = 0;
@@ -157,6 +162,25 @@ impl Serialize for ElementSegment {
 	}
 }
 
+impl SerializedSize for ElementSegment {
+	fn serialized_size(&self) -> usize {
+		// Flags, followed by the index only for active segments with a non-zero index.
+		#[cfg(feature="bulk")]
+		let header = if !self.passive && self.index != 0 {
+			1 + unsigned_size(self.index.into())
+		} else {
+			1
+		};
+		#[cfg(not(feature="bulk"))]
+		let header = unsigned_size(self.index.into());
+
+		header
+			+ self.offset.as_ref().map_or(0, SerializedSize::serialized_size)
+			+ unsigned_size(self.members.len() as u64)
+			+ self.members.iter().map(|&member| unsigned_size(member.into())).sum::<usize>()
+	}
+}
+
 /// Data segment definition.
 #[derive(Clone, Debug, PartialEq)]
 pub struct DataSegment {
@@ -287,3 +311,21 @@ impl Serialize for DataSegment {
 		Ok(())
 	}
 }
+
+impl SerializedSize for DataSegment {
+	fn serialized_size(&self) -> usize {
+		// Flags, followed by the index only for active segments with a non-zero index.
+		#[cfg(feature="bulk")]
+		let header = if !self.passive && self.index != 0 {
+			1 + unsigned_size(self.index.into())
+		} else {
+			1
+		};
+		#[cfg(not(feature="bulk"))]
+		let header = unsigned_size(self.index.into());
+
+		header
+			+ self.offset.as_ref().map_or(0, SerializedSize::serialized_size)
+			+ prefixed_size(self.value.len())
+	}
+}
@@ -1,9 +1,10 @@
 use alloc::vec::Vec;
 use crate::io;
 use super::{
-	Deserialize, Serialize, Error, VarUint7, VarInt7, CountedList,
+	Deserialize, Serialize, SerializedSize, Error, VarUint7, VarInt7, CountedList,
 	CountedListWriter,
 };
+use super::primitives::counted_list_size;
 use core::fmt;
 
 /// Type definition in types section. Currently can be only of the function type.
@@ -31,6 +32,14 @@ impl Serialize for Type {
 	}
 }
 
+impl SerializedSize for Type {
+	fn serialized_size(&self) -> usize {
+		match *self {
+			Type::Function(ref fn_type) => fn_type.serialized_size(),
+		}
+	}
+}
+
 /// Value type.
 #[derive(Clone, Copy, Debug, PartialEq, Hash, Eq)]
 pub enum ValueType {
@@ -45,6 +54,12 @@ pub enum ValueType {
 	#[cfg(feature="simd")]
 	/// 128-bit SIMD register
 	V128,
+	#[cfg(feature="reference_types")]
+	/// Reference to a function
+	FuncRef,
+	#[cfg(feature="reference_types")]
+	/// Reference to a host object
+	ExternRef,
 }
 
 impl Deserialize for ValueType {
@@ -60,6 +75,10 @@ impl Deserialize for ValueType {
 			-0x04 => Ok(ValueType::F64),
 			#[cfg(feature="simd")]
 			-0x05 => Ok(ValueType::V128),
+			#[cfg(feature="reference_types")]
+			-0x10 => Ok(ValueType::FuncRef),
+			#[cfg(feature="reference_types")]
+			-0x11 => Ok(ValueType::ExternRef),
 			_ => Err(Error::UnknownValueType(val.into())),
 		}
 	}
@@ -76,12 +95,22 @@ impl Serialize for ValueType {
 			ValueType::F64 => -0x04,
 			#[cfg(feature="simd")]
 			ValueType::V128 => -0x05,
+			#[cfg(feature="reference_types")]
+			ValueType::FuncRef => -0x10,
+			#[cfg(feature="reference_types")]
+			ValueType::ExternRef => -0x11,
 		}.into();
 		val.serialize(writer)?;
 		Ok(())
 	}
 }
 
+impl SerializedSize for ValueType {
+	fn serialized_size(&self) -> usize {
+		1
+	}
+}
+
 impl fmt::Display for ValueType {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match *self {
@@ -91,6 +120,10 @@ impl fmt::Display for ValueType {
 			ValueType::F64 => write!(f, "f64"),
 			#[cfg(feature="simd")]
 			ValueType::V128 => write!(f, "v128"),
+			#[cfg(feature="reference_types")]
+			ValueType::FuncRef => write!(f, "funcref"),
+			#[cfg(feature="reference_types")]
+			ValueType::ExternRef => write!(f, "externref"),
 		}
 	}
 }
@@ -117,6 +150,10 @@ impl Deserialize for BlockType {
 			-0x04 => Ok(BlockType::Value(ValueType::F64)),
 			#[cfg(feature="simd")]
 			0x7b => Ok(BlockType::Value(ValueType::V128)),
+			#[cfg(feature="reference_types")]
+			-0x10 => Ok(BlockType::Value(ValueType::FuncRef)),
+			#[cfg(feature="reference_types")]
+			-0x11 => Ok(BlockType::Value(ValueType::ExternRef)),
 			-0x40 => Ok(BlockType::NoResult),
 			_ => Err(Error::UnknownValueType(val.into())),
 		}
@@ -135,12 +172,22 @@ impl Serialize for BlockType {
 			BlockType::Value(ValueType::F64) => -0x04,
 			#[cfg(feature="simd")]
 			BlockType::Value(ValueType::V128) => 0x7b,
+			#[cfg(feature="reference_types")]
+			BlockType::Value(ValueType::FuncRef) => -0x10,
+			#[cfg(feature="reference_types")]
+			BlockType::Value(ValueType::ExternRef) => -0x11,
 		}.into();
 		val.serialize(writer)?;
 		Ok(())
 	}
 }
 
+impl SerializedSize for BlockType {
+	fn serialized_size(&self) -> usize {
+		1
+	}
+}
+
 /// Function signature type.
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub struct FunctionType {
@@ -228,11 +275,20 @@ impl Serialize for FunctionType {
 	}
 }
 
+impl SerializedSize for FunctionType {
+	fn serialized_size(&self) -> usize {
+		1 + counted_list_size(&self.params) + counted_list_size(&self.results)
+	}
+}
+
 /// Table element type.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum TableElementType {
 	/// A reference to a function with any signature.
 	AnyFunc,
+	/// An opaque reference owned by the host.
+	#[cfg(feature="reference_types")]
+	ExternRef,
 }
 
 impl Deserialize for TableElementType {
@@ -243,6 +299,8 @@ impl Deserialize for TableElementType {
 
 		match val.into() {
 			-0x10 => Ok(TableElementType::AnyFunc),
+			#[cfg(feature="reference_types")]
+			-0x11 => Ok(TableElementType::ExternRef),
 			_ => Err(Error::UnknownTableElementType(val.into())),
 		}
 	}
@@ -254,8 +312,16 @@ impl Serialize for TableElementType {
 	fn serialize<W: io::Write>(self, writer: &mut W) -> Result<(), Self::Error> {
 		let val: VarInt7 = match self {
 			TableElementType::AnyFunc => -0x10,
+			#[cfg(feature="reference_types")]
+			TableElementType::ExternRef => -0x11,
 		}.into();
 		val.serialize(writer)?;
 		Ok(())
 	}
 }
+
+impl SerializedSize for TableElementType {
+	fn serialized_size(&self) -> usize {
+		1
+	}
+}
@@ -37,6 +37,11 @@ pub trait Read {
 	///
 	/// If there is not enough data in this read then `UnexpectedEof` will be returned.
 	fn read(&mut self, buf: &mut [u8]) -> Result<()>;
+
+	/// Number of bytes left in this read, if known.
+	fn remaining(&self) -> Option<usize> {
+		None
+	}
 }
 
 /// Reader that saves the last position.
@@ -70,6 +75,10 @@ impl<T: AsRef<[u8]>> Read for Cursor<T> {
 		self.pos += requested;
 		Ok(())
 	}
+
+	fn remaining(&self) -> Option<usize> {
+		Some(self.inner.as_ref().len() - self.pos)
+	}
 }
 
 #[cfg(not(feature = "std"))]
@@ -111,6 +120,7 @@ mod tests {
 		assert!(cursor.read(&mut buf[..]).is_ok());
 		assert_eq!(buf[0], 0x7Fu8);
 		assert_eq!(cursor.position(), 2);
+		assert_eq!(cursor.remaining(), Some(0));
 	}
 
 	#[test]
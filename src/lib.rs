@@ -13,6 +13,7 @@ mod io;
 pub use elements::{
 	Error as SerializationError,
 	deserialize_buffer,
+	deserialize_buffer_lenient,
 	serialize,
 	peek_size,
 };